@@ -0,0 +1,311 @@
+// shift_left, shift_right は生ポインタを受け取るので unsafe fn にしている
+// 長さ len の配列の先頭を指すポインタを渡す前提
+#![allow(clippy::missing_safety_doc)]
+
+use std::{mem::ManuallyDrop, ptr};
+
+use array_stack::alloc_array;
+use interface::{Deque, List};
+
+// a[j], a[(j+1)%len], ..., a[(j+n-1)%len] に要素を持つ循環配列
+// 先頭と末尾のうち近いほうの要素をずらす
+pub struct ArrayDeque<T> {
+    a: *mut [Option<T>],
+    j: usize,
+    n: usize,
+}
+
+impl<T> ArrayDeque<T> {
+    pub fn new() -> Self {
+        Self {
+            a: alloc_array(1),
+            j: 0,
+            n: 0,
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        unsafe { &*self.a }.len()
+    }
+
+    // i 番目の要素を置く位置
+    fn index(&self, i: usize) -> usize {
+        (self.j + i) % self.capacity()
+    }
+
+    // 配列の長さを max(2n, 1) にして、先頭の要素を b[0] に置く
+    // O(n) time
+    fn resize(&mut self) {
+        let len = self.capacity();
+        let a = self.a as *mut Option<T>;
+        let b = alloc_array(1.max(self.n * 2));
+        // a[j..] と a[..] の 2 つに分けて移す
+        let k = self.n.min(len - self.j);
+        unsafe { ptr::copy_nonoverlapping(a.add(self.j), b as *mut Option<T>, k) };
+        unsafe { ptr::copy_nonoverlapping(a, (b as *mut Option<T>).add(k), self.n - k) };
+        // 要素は b に移したので古い配列は中身を drop せずに解放する
+        drop(unsafe { Box::from_raw(self.a as *mut [ManuallyDrop<Option<T>>]) });
+        self.a = b;
+        self.j = 0;
+    }
+}
+
+// 以下の 2 つは長さ len の循環配列 a の a[s], a[s+1], ..., a[s+m-1] (添字は len で割った余り) をひとつずらす
+// 移動先の空いている位置は None か未初期化か、読み出し済みの要素とする
+// ずらしたあとに空いた位置は drop せずに上書きする
+// 配列の端で分かれた部分ごとに ptr::copy でまとめて移す
+// SEList のブロックでも使う
+
+// a[s-1..s+m-1] に移す
+// O(m) time
+pub unsafe fn shift_left<T>(a: *mut T, len: usize, s: usize, m: usize) {
+    if m == 0 {
+        return;
+    }
+    if s == 0 {
+        ptr::copy_nonoverlapping(a, a.add(len - 1), 1);
+        ptr::copy(a.add(1), a, m - 1);
+    } else if s + m <= len {
+        ptr::copy(a.add(s), a.add(s - 1), m);
+    } else {
+        // a[s..len] と a[..s+m-len] に分かれている
+        ptr::copy(a.add(s), a.add(s - 1), len - s);
+        ptr::copy_nonoverlapping(a, a.add(len - 1), 1);
+        ptr::copy(a.add(1), a, s + m - len - 1);
+    }
+}
+
+// a[s+1..s+m+1] に移す
+// O(m) time
+pub unsafe fn shift_right<T>(a: *mut T, len: usize, s: usize, m: usize) {
+    if m == 0 {
+        return;
+    }
+    if s + m < len {
+        ptr::copy(a.add(s), a.add(s + 1), m);
+    } else {
+        // 移動先が a[s+1..len] と a[..s+m+1-len] に分かれている
+        ptr::copy(a, a.add(1), s + m - len);
+        ptr::copy_nonoverlapping(a.add(len - 1), a, 1);
+        ptr::copy(a.add(s), a.add(s + 1), len - 1 - s);
+    }
+}
+
+impl<T> Default for ArrayDeque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> List<T> for ArrayDeque<T> {
+    fn size(&self) -> usize {
+        self.n
+    }
+
+    // O(1) time
+    fn get(&self, i: usize) -> Option<&T> {
+        if i < self.n {
+            let a = unsafe { &*self.a };
+            a[self.index(i)].as_ref()
+        } else {
+            None
+        }
+    }
+
+    // O(1) time
+    fn set(&self, i: usize, x: T) -> T {
+        assert!(i < self.n, "expect `i` < ArrayDeque::size()");
+        let y = unsafe { &mut *self.a }[self.index(i)].replace(x);
+        y.unwrap()
+    }
+
+    // amortized O(1 + min(i, n-i)) time
+    fn add(&mut self, i: usize, x: T) {
+        assert!(i <= self.n, "expect `i` <= ArrayDeque::size()");
+        if self.n + 1 > self.capacity() {
+            self.resize();
+        }
+        let len = self.capacity();
+        let a = self.a as *mut Option<T>;
+        if i < self.n / 2 {
+            // a[j..j+i] を左にひとつずらす
+            // a[j-1] は None
+            unsafe { shift_left(a, len, self.j, i) };
+            self.j = (self.j + len - 1) % len;
+        } else {
+            // a[j+i..j+n] を右にひとつずらす
+            // a[j+n] は None
+            unsafe { shift_right(a, len, (self.j + i) % len, self.n - i) };
+        }
+        // a[j+i] は隣と同じものを指しているので drop せずに書き込む
+        unsafe { ptr::write(a.add((self.j + i) % len), Some(x)) };
+        self.n += 1;
+    }
+
+    // amortized O(1 + min(i, n-i)) time
+    fn remove(&mut self, i: usize) -> T {
+        assert!(i < self.n, "expect `i` < ArrayDeque::size()");
+        let len = self.capacity();
+        let a = self.a as *mut Option<T>;
+        let x = unsafe { ptr::read(a.add((self.j + i) % len)) };
+        if i < self.n / 2 {
+            // a[j..j+i] を右にひとつずらす
+            unsafe { shift_right(a, len, self.j, i) };
+            unsafe { ptr::write(a.add(self.j), None) };
+            self.j = (self.j + 1) % len;
+        } else {
+            // a[j+i+1..j+n] を左にひとつずらす
+            unsafe { shift_left(a, len, (self.j + i + 1) % len, self.n - i - 1) };
+            unsafe { ptr::write(a.add((self.j + self.n - 1) % len), None) };
+        }
+        self.n -= 1;
+        if self.capacity() >= self.n * 3 {
+            self.resize();
+        }
+        x.unwrap()
+    }
+}
+
+impl<T> Deque<T> for ArrayDeque<T> {
+    // amortized O(1) time
+    fn add_first(&mut self, x: T) {
+        self.add(0, x);
+    }
+
+    // amortized O(1) time
+    fn add_last(&mut self, x: T) {
+        self.add(self.n, x);
+    }
+
+    // amortized O(1) time
+    fn remove_first(&mut self) -> Option<T> {
+        if self.n == 0 {
+            None
+        } else {
+            Some(self.remove(0))
+        }
+    }
+
+    // amortized O(1) time
+    fn remove_last(&mut self) -> Option<T> {
+        if self.n == 0 {
+            None
+        } else {
+            Some(self.remove(self.n - 1))
+        }
+    }
+
+    // O(1) time
+    fn get_first(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    // O(1) time
+    fn get_last(&self) -> Option<&T> {
+        if self.n == 0 {
+            None
+        } else {
+            self.get(self.n - 1)
+        }
+    }
+}
+
+impl<T> Drop for ArrayDeque<T> {
+    fn drop(&mut self) {
+        drop(unsafe { Box::from_raw(self.a) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ArrayDeque;
+use interface::{Deque, List};
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_set_get() {
+        let mut list = ArrayDeque::new();
+        list.add(0, 'a');
+        list.add(1, 'b');
+        list.add(2, 'c');
+        // a b c
+        assert_eq!(list.set(1, 'x'), 'b');
+        // a x c
+        assert_eq!(list.get(0), Some(&'a'));
+        assert_eq!(list.get(1), Some(&'x'));
+        assert_eq!(list.get(2), Some(&'c'));
+        assert_eq!(list.get(3), None);
+    }
+
+    #[test]
+    fn test_random_list() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut v = Vec::new();
+        let mut list = ArrayDeque::new();
+        for _ in 0..1000 {
+            if v.is_empty() || rng.gen_bool(0.6) {
+                let i = rng.gen_range(0..=v.len());
+                let x = rng.gen::<u32>();
+                v.insert(i, x);
+                list.add(i, x);
+            } else {
+                let i = rng.gen_range(0..v.len());
+                assert_eq!(list.remove(i), v.remove(i));
+            }
+            assert_eq!(list.size(), v.len());
+        }
+        for i in 0..v.len() {
+            assert_eq!(list.get(i), v.get(i));
+        }
+    }
+
+    #[test]
+    fn test_drop() {
+        // 要素をずらしても二重に drop したり drop し忘れたりしない
+        let mut rng = SmallRng::seed_from_u64(0);
+        let x = Rc::new(());
+        let mut list = ArrayDeque::new();
+        for _ in 0..1000 {
+            if list.size() == 0 || rng.gen_bool(0.6) {
+                let i = rng.gen_range(0..=list.size());
+                list.add(i, Rc::clone(&x));
+            } else {
+                let i = rng.gen_range(0..list.size());
+                list.remove(i);
+            }
+            assert_eq!(Rc::strong_count(&x), list.size() + 1);
+        }
+        drop(list);
+        assert_eq!(Rc::strong_count(&x), 1);
+    }
+
+    #[test]
+    fn test_random_deque() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut deque = ArrayDeque::new();
+        let mut vec_deque = VecDeque::new();
+        for _ in 0..1000 {
+            let x = rng.gen::<u32>();
+            match rng.gen_range(0..4) {
+                0 => {
+                    deque.add_first(x);
+                    vec_deque.push_front(x);
+                }
+                1 => {
+                    deque.add_last(x);
+                    vec_deque.push_back(x);
+                }
+                2 => {
+                    assert_eq!(deque.remove_first(), vec_deque.pop_front());
+                }
+                _ => {
+                    assert_eq!(deque.remove_last(), vec_deque.pop_back());
+                }
+            }
+            assert_eq!(deque.get_first(), vec_deque.front());
+            assert_eq!(deque.get_last(), vec_deque.back());
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use interface::Queue;
+
+// a[j], a[(j+1)%len], ..., a[(j+n-1)%len] に要素を持つ循環配列
+pub struct ArrayQueue<T> {
+    a: Box<[Option<T>]>,
+    j: usize,
+    n: usize,
+}
+
+impl<T> ArrayQueue<T> {
+    pub fn new() -> Self {
+        Self {
+            a: Self::alloc_array(1),
+            j: 0,
+            n: 0,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    fn alloc_array(len: usize) -> Box<[Option<T>]> {
+        (0..len).map(|_| None).collect()
+    }
+
+    // 配列の長さを max(2n, 1) にして、先頭の要素を b[0] に置く
+    // O(n) time
+    fn resize(&mut self) {
+        let mut b = Self::alloc_array(1.max(self.n * 2));
+        for (k, y) in b.iter_mut().take(self.n).enumerate() {
+            *y = self.a[(self.j + k) % self.a.len()].take();
+        }
+        self.a = b;
+        self.j = 0;
+    }
+}
+
+impl<T> Default for ArrayQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Queue<T> for ArrayQueue<T> {
+    // amortized O(1) time
+    fn add(&mut self, x: T) {
+        if self.n + 1 > self.a.len() {
+            self.resize();
+        }
+        let i = (self.j + self.n) % self.a.len();
+        self.a[i] = Some(x);
+        self.n += 1;
+    }
+
+    // amortized O(1) time
+    fn remove(&mut self) -> Option<T> {
+        if self.n == 0 {
+            return None;
+        }
+        let x = self.a[self.j].take();
+        debug_assert!(x.is_some());
+        self.j = (self.j + 1) % self.a.len();
+        self.n -= 1;
+        if self.a.len() >= self.n * 3 {
+            self.resize();
+        }
+        x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ArrayQueue;
+    use interface::Queue;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+    use std::collections::VecDeque;
+
+    #[test]
+    fn test_add_remove() {
+        let mut queue = ArrayQueue::new();
+        assert_eq!(queue.remove(), None);
+        queue.add('a');
+        queue.add('b');
+        assert_eq!(queue.remove(), Some('a'));
+        queue.add('c');
+        assert_eq!(queue.remove(), Some('b'));
+        assert_eq!(queue.remove(), Some('c'));
+        assert_eq!(queue.remove(), None);
+        assert_eq!(queue.size(), 0);
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut queue = ArrayQueue::new();
+        let mut vec_deque = VecDeque::new();
+        for _ in 0..1000 {
+            if rng.gen_bool(0.6) {
+                let x = rng.gen::<u32>();
+                queue.add(x);
+                vec_deque.push_back(x);
+            } else {
+                assert_eq!(queue.remove(), vec_deque.pop_front());
+            }
+            assert_eq!(queue.size(), vec_deque.len());
+        }
+        while let Some(x) = vec_deque.pop_front() {
+            assert_eq!(queue.remove(), Some(x));
+        }
+        assert_eq!(queue.remove(), None);
+    }
+}
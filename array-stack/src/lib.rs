@@ -0,0 +1,321 @@
+use std::{mem::ManuallyDrop, ptr};
+
+use interface::{List, Stack};
+
+// 長さ len の配列を確保する
+// List::set が &self を受け取るので Box のままではなく生ポインタで持つ
+// ArrayDeque, RootishArrayStack でも使う
+pub fn alloc_array<T>(len: usize) -> *mut [Option<T>] {
+    let a: Vec<Option<T>> = (0..len).map(|_| None).collect();
+    Box::into_raw(a.into_boxed_slice())
+}
+
+pub struct ArrayStack<T> {
+    a: *mut [Option<T>],
+    n: usize,
+}
+
+impl<T> ArrayStack<T> {
+    pub fn new() -> Self {
+        Self {
+            a: alloc_array(1),
+            n: 0,
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        unsafe { &*self.a }.len()
+    }
+
+    // 配列の長さを max(2n, 1) にする
+    // O(n) time
+    fn resize(&mut self) {
+        let b = alloc_array(1.max(self.n * 2));
+        for i in 0..self.n {
+            let x = unsafe { &mut *self.a }[i].take();
+            unsafe { (&mut *b)[i] = x };
+        }
+        drop(unsafe { Box::from_raw(self.a) });
+        self.a = b;
+    }
+}
+
+impl<T> Default for ArrayStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> List<T> for ArrayStack<T> {
+    fn size(&self) -> usize {
+        self.n
+    }
+
+    // O(1) time
+    fn get(&self, i: usize) -> Option<&T> {
+        if i < self.n {
+            let a = unsafe { &*self.a };
+            a[i].as_ref()
+        } else {
+            None
+        }
+    }
+
+    // O(1) time
+    fn set(&self, i: usize, x: T) -> T {
+        assert!(i < self.n, "expect `i` < ArrayStack::size()");
+        let y = unsafe { &mut *self.a }[i].replace(x);
+        y.unwrap()
+    }
+
+    // amortized O(n-i) time
+    fn add(&mut self, i: usize, x: T) {
+        assert!(i <= self.n, "expect `i` <= ArrayStack::size()");
+        if self.n + 1 > self.capacity() {
+            self.resize();
+        }
+        let a = unsafe { &mut *self.a };
+        for j in (i + 1..=self.n).rev() {
+            a[j] = a[j - 1].take();
+        }
+        a[i] = Some(x);
+        self.n += 1;
+    }
+
+    // amortized O(n-i) time
+    fn remove(&mut self, i: usize) -> T {
+        assert!(i < self.n, "expect `i` < ArrayStack::size()");
+        let a = unsafe { &mut *self.a };
+        let x = a[i].take();
+        for j in i..(self.n - 1) {
+            a[j] = a[j + 1].take();
+        }
+        self.n -= 1;
+        if self.capacity() >= self.n * 3 {
+            self.resize();
+        }
+        x.unwrap()
+    }
+}
+
+impl<T> Stack<T> for ArrayStack<T> {
+    // amortized O(1) time
+    fn push(&mut self, x: T) {
+        self.add(self.n, x);
+    }
+
+    // amortized O(1) time
+    fn pop(&mut self) -> Option<T> {
+        if self.n == 0 {
+            None
+        } else {
+            Some(self.remove(self.n - 1))
+        }
+    }
+}
+
+impl<T> Drop for ArrayStack<T> {
+    fn drop(&mut self) {
+        drop(unsafe { Box::from_raw(self.a) });
+    }
+}
+
+// 要素の移動を ptr::copy でまとめて行う ArrayStack
+pub struct FastArrayStack<T> {
+    a: *mut [Option<T>],
+    n: usize,
+}
+
+impl<T> FastArrayStack<T> {
+    pub fn new() -> Self {
+        Self {
+            a: alloc_array(1),
+            n: 0,
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        unsafe { &*self.a }.len()
+    }
+
+    // O(n) time
+    fn resize(&mut self) {
+        let b = alloc_array(1.max(self.n * 2));
+        unsafe { ptr::copy_nonoverlapping(self.a as *const Option<T>, b as *mut Option<T>, self.n) };
+        // 要素は b に移したので古い配列は中身を drop せずに解放する
+        drop(unsafe { Box::from_raw(self.a as *mut [ManuallyDrop<Option<T>>]) });
+        self.a = b;
+    }
+}
+
+impl<T> Default for FastArrayStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> List<T> for FastArrayStack<T> {
+    fn size(&self) -> usize {
+        self.n
+    }
+
+    // O(1) time
+    fn get(&self, i: usize) -> Option<&T> {
+        if i < self.n {
+            let a = unsafe { &*self.a };
+            a[i].as_ref()
+        } else {
+            None
+        }
+    }
+
+    // O(1) time
+    fn set(&self, i: usize, x: T) -> T {
+        assert!(i < self.n, "expect `i` < FastArrayStack::size()");
+        let y = unsafe { &mut *self.a }[i].replace(x);
+        y.unwrap()
+    }
+
+    // amortized O(n-i) time
+    fn add(&mut self, i: usize, x: T) {
+        assert!(i <= self.n, "expect `i` <= FastArrayStack::size()");
+        if self.n + 1 > self.capacity() {
+            self.resize();
+        }
+        let a = self.a as *mut Option<T>;
+        // a[i..n] を a[i+1..n+1] にずらす
+        // a[n] は None なので上書きしてよい
+        unsafe { ptr::copy(a.add(i), a.add(i + 1), self.n - i) };
+        // a[i] は a[i+1] と同じものを指しているので drop せずに書き込む
+        unsafe { ptr::write(a.add(i), Some(x)) };
+        self.n += 1;
+    }
+
+    // amortized O(n-i) time
+    fn remove(&mut self, i: usize) -> T {
+        assert!(i < self.n, "expect `i` < FastArrayStack::size()");
+        let a = self.a as *mut Option<T>;
+        let x = unsafe { ptr::read(a.add(i)) };
+        // a[i+1..n] を a[i..n-1] にずらす
+        unsafe { ptr::copy(a.add(i + 1), a.add(i), self.n - i - 1) };
+        unsafe { ptr::write(a.add(self.n - 1), None) };
+        self.n -= 1;
+        if self.capacity() >= self.n * 3 {
+            self.resize();
+        }
+        x.unwrap()
+    }
+}
+
+impl<T> Stack<T> for FastArrayStack<T> {
+    // amortized O(1) time
+    fn push(&mut self, x: T) {
+        self.add(self.n, x);
+    }
+
+    // amortized O(1) time
+    fn pop(&mut self) -> Option<T> {
+        if self.n == 0 {
+            None
+        } else {
+            Some(self.remove(self.n - 1))
+        }
+    }
+}
+
+impl<T> Drop for FastArrayStack<T> {
+    fn drop(&mut self) {
+        drop(unsafe { Box::from_raw(self.a) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArrayStack, FastArrayStack};
+    use interface::{List, Stack};
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+    use std::rc::Rc;
+
+    #[test]
+    fn test_push_pop() {
+        let mut stack = ArrayStack::new();
+        let mut fast_stack = FastArrayStack::new();
+        assert_eq!(stack.pop(), None);
+        assert_eq!(fast_stack.pop(), None);
+        for c in ['a', 'b', 'c'] {
+            stack.push(c);
+            fast_stack.push(c);
+        }
+        for c in ['c', 'b', 'a'] {
+            assert_eq!(stack.pop(), Some(c));
+            assert_eq!(fast_stack.pop(), Some(c));
+        }
+        assert_eq!(stack.pop(), None);
+        assert_eq!(fast_stack.pop(), None);
+    }
+
+    #[test]
+    fn test_set_get() {
+        let mut list = ArrayStack::new();
+        let mut fast_list = FastArrayStack::new();
+        for (i, c) in ['a', 'b', 'c'].into_iter().enumerate() {
+            list.add(i, c);
+            fast_list.add(i, c);
+        }
+        // a b c
+        assert_eq!(list.set(1, 'x'), 'b');
+        assert_eq!(fast_list.set(1, 'x'), 'b');
+        // a x c
+        assert_eq!(list.get(1), Some(&'x'));
+        assert_eq!(fast_list.get(1), Some(&'x'));
+        assert_eq!(list.get(3), None);
+        assert_eq!(fast_list.get(3), None);
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut v = Vec::new();
+        let mut list = ArrayStack::new();
+        let mut fast_list = FastArrayStack::new();
+        for _ in 0..1000 {
+            if v.is_empty() || rng.gen_bool(0.6) {
+                let i = rng.gen_range(0..=v.len());
+                let x = rng.gen::<u32>();
+                v.insert(i, x);
+                list.add(i, x);
+                fast_list.add(i, x);
+            } else {
+                let i = rng.gen_range(0..v.len());
+                let x = v.remove(i);
+                assert_eq!(list.remove(i), x);
+                assert_eq!(fast_list.remove(i), x);
+            }
+            assert_eq!(list.size(), v.len());
+            assert_eq!(fast_list.size(), v.len());
+            // n <= a.len() <= 3n を保つ (n = 0 のときは a.len() = 1)
+            assert!(list.capacity() <= (v.len() * 3).max(1));
+            assert!(fast_list.capacity() <= (v.len() * 3).max(1));
+        }
+        for i in 0..v.len() {
+            assert_eq!(list.get(i), v.get(i));
+            assert_eq!(fast_list.get(i), v.get(i));
+        }
+    }
+
+    #[test]
+    fn test_drop() {
+        // ptr::copy でずらした要素が二重に drop されたり、drop されずに残ったりしない
+        let x = Rc::new(());
+        let mut fast_list = FastArrayStack::new();
+        for i in 0..10 {
+            fast_list.add(i / 2, Rc::clone(&x));
+        }
+        for i in 0..5 {
+            fast_list.remove(i);
+        }
+        assert_eq!(Rc::strong_count(&x), 6);
+        drop(fast_list);
+        assert_eq!(Rc::strong_count(&x), 1);
+    }
+}
@@ -2,9 +2,15 @@ use std::time::{Duration, Instant};
 
 use rand::{rngs::SmallRng, Rng, SeedableRng};
 
+use array_deque::ArrayDeque;
+use array_stack::{ArrayStack, FastArrayStack};
 use dllist::DLList;
+use dual_array_deque::DualArrayDeque;
 use interface::List;
+use rootish_array_stack::RootishArrayStack;
+use se_list::SEList;
 use skiplist_list::SkipListList;
+use treap_list::TreapList;
 
 struct VecAsList<T>(Vec<T>);
 impl<T> List<T> for VecAsList<T> {
@@ -49,13 +55,41 @@ fn main() {
     let elapsed = add_remove(DLList::new(), a.clone(), b.clone());
     println!("DLList {} ms", elapsed.as_millis());
 
+    let elapsed = add_remove(SEList::new(64), a.clone(), b.clone());
+    println!("SEList {} ms", elapsed.as_millis());
+
     let elapsed = add_remove(VecAsList(Vec::new()), a.clone(), b.clone());
     println!("std::vec::Vec {} ms", elapsed.as_millis());
 
+    let elapsed = add_remove(ArrayStack::new(), a.clone(), b.clone());
+    println!("ArrayStack {} ms", elapsed.as_millis());
+
+    let elapsed = add_remove(FastArrayStack::new(), a.clone(), b.clone());
+    println!("FastArrayStack {} ms", elapsed.as_millis());
+
+    let elapsed = add_remove(ArrayDeque::new(), a.clone(), b.clone());
+    println!("ArrayDeque {} ms", elapsed.as_millis());
+
+    let elapsed = add_remove(DualArrayDeque::new(), a.clone(), b.clone());
+    println!("DualArrayDeque {} ms", elapsed.as_millis());
+
+    let elapsed = add_remove(RootishArrayStack::new(), a.clone(), b.clone());
+    println!("RootishArrayStack {} ms", elapsed.as_millis());
+
     let elapsed = add_remove(SkipListList::new(), a.clone(), b.clone());
     println!("SkipListList {} ms", elapsed.as_millis());
 
-    // DLList 5038 ms
-    // std::vec::Vec 160 ms
-    // SkipListList 48 ms
+    let elapsed = add_remove(TreapList::new(), a.clone(), b.clone());
+    println!("TreapList {} ms", elapsed.as_millis());
+
+    // DLList 9889 ms
+    // SEList 102 ms
+    // std::vec::Vec 262 ms
+    // ArrayStack 1673 ms
+    // FastArrayStack 568 ms
+    // ArrayDeque 286 ms
+    // DualArrayDeque 1034 ms
+    // RootishArrayStack 3169 ms
+    // SkipListList 65 ms
+    // TreapList 63 ms
 }
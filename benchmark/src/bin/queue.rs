@@ -3,6 +3,7 @@ use std::time::{Duration, Instant};
 
 use rand::{rngs::SmallRng, Rng, SeedableRng};
 
+use array_queue::ArrayQueue;
 use interface::Queue;
 use sllist::SLList;
 
@@ -58,7 +59,11 @@ fn main() {
     let elapsed = run(SLList::new(), a.clone());
     println!("SLList {} ms", elapsed.as_millis());
 
-    // std::vec::Vec 74067 ms
-    // std::collections::VecDeque 3 ms
-    // SLList 38 ms
+    let elapsed = run(ArrayQueue::new(), a.clone());
+    println!("ArrayQueue {} ms", elapsed.as_millis());
+
+    // std::vec::Vec 43118 ms
+    // std::collections::VecDeque 4 ms
+    // SLList 39 ms
+    // ArrayQueue 26 ms
 }
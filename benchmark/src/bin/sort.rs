@@ -0,0 +1,65 @@
+use std::time::{Duration, Instant};
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use sorting::{counting_sort, heap_sort, merge_sort, quick_sort, radix_sort};
+
+// sort の返り値 (比較の回数など) は使わない
+fn run<R>(sort: impl FnOnce(&mut [u32]) -> R, mut a: Vec<u32>) -> Duration {
+    let now = Instant::now();
+    sort(&mut a);
+    let elapsed = now.elapsed();
+    assert!(a.windows(2).all(|w| w[0] <= w[1]));
+    elapsed
+}
+
+fn main() {
+    let mut rng = SmallRng::seed_from_u64(122333);
+    let n = 5_000_000;
+    let mut a = vec![0_u32; n];
+    rng.fill(&mut a[..]);
+
+    let elapsed = run(|a| a.sort_unstable(), a.clone());
+    println!("slice::sort_unstable {} ms", elapsed.as_millis());
+
+    let elapsed = run(|a| merge_sort(a, Ord::cmp), a.clone());
+    println!("merge_sort {} ms", elapsed.as_millis());
+
+    let elapsed = run(|a| quick_sort(a, Ord::cmp), a.clone());
+    println!("quick_sort {} ms", elapsed.as_millis());
+
+    let elapsed = run(|a| heap_sort(a, Ord::cmp), a.clone());
+    println!("heap_sort {} ms", elapsed.as_millis());
+
+    for d in [8, 11, 16] {
+        let elapsed = run(|a| radix_sort(a, d), a.clone());
+        println!("radix_sort (d = {}) {} ms", d, elapsed.as_millis());
+    }
+
+    // counting_sort は値の範囲を n 未満にして測る
+    let k = n as u32;
+    let b: Vec<u32> = a.iter().map(|x| x % k).collect();
+
+    let elapsed = run(|a| a.sort_unstable(), b.clone());
+    println!("[< n] slice::sort_unstable {} ms", elapsed.as_millis());
+
+    let elapsed = run(|a| counting_sort(a, n), b.clone());
+    println!("[< n] counting_sort {} ms", elapsed.as_millis());
+
+    let elapsed = run(|a| radix_sort(a, 11), b.clone());
+    println!("[< n] radix_sort (d = 11) {} ms", elapsed.as_millis());
+
+    // counting_sort は長さ k = n の配列を数え上げに使うので、radix_sort より遅い
+
+    // slice::sort_unstable 119 ms
+    // merge_sort 1423 ms
+    // quick_sort 930 ms
+    // heap_sort 1571 ms
+    // radix_sort (d = 8) 171 ms
+    // radix_sort (d = 11) 161 ms
+    // radix_sort (d = 16) 147 ms
+
+    // [< n] slice::sort_unstable 155 ms
+    // [< n] counting_sort 334 ms
+    // [< n] radix_sort (d = 11) 118 ms
+}
@@ -1,13 +1,18 @@
 use std::collections::BTreeSet;
 use std::time::{Duration, Instant};
 
+use binary_search_tree::BinarySearchTree;
 use binary_trie::{BinaryTrie, IntValue};
+use btree::{BTree, FixedSize, MemoryBlockStore};
 use rand::{rngs::SmallRng, Rng, SeedableRng};
 
 use interface::SSet;
+use red_black_tree::RedBlackTree;
 use scapegoat_tree::ScapegoatTree;
 use skiplist_sset::SkipListSSet;
 use treap::Treap;
+use x_fast_trie::XFastTrie;
+use y_fast_trie::YFastTrie;
 
 struct MyBTreeSet<T>(BTreeSet<T>);
 impl<T> SSet<T> for MyBTreeSet<T>
@@ -23,8 +28,8 @@ where
     fn remove(&mut self, x: &T) -> bool {
         self.0.remove(x)
     }
-    fn find(&self, _: &T) -> Option<&T> {
-        unreachable!()
+    fn find(&self, x: &T) -> Option<&T> {
+        self.0.range(x..).next()
     }
 }
 
@@ -39,28 +44,72 @@ fn add_remove<T>(mut set: impl SSet<T>, a: Vec<T>, b: Vec<T>) -> Duration {
     now.elapsed()
 }
 
-fn run<T>(label: &str, a: Vec<T>, b: Vec<T>)
-where
-    T: Clone + Ord + IntValue,
-{
-    let elapsed = add_remove(MyBTreeSet(BTreeSet::new()), a.clone(), b.clone());
+// a をすべて追加し、b をすべて find してから、b をすべて削除する
+// (追加と削除にかかった時間, find にかかった時間, find で見つかった個数) を返す
+fn add_find_remove<T>(mut set: impl SSet<T>, a: Vec<T>, b: Vec<T>) -> (Duration, Duration, usize) {
+    let now = Instant::now();
+    for a in a {
+        set.add(a);
+    }
+    let mut elapsed = now.elapsed();
+    let now = Instant::now();
+    let found = b.iter().filter(|b| set.find(b).is_some()).count();
+    let find_elapsed = now.elapsed();
+    let now = Instant::now();
+    for b in b {
+        set.remove(&b);
+    }
+    elapsed += now.elapsed();
+    (elapsed, find_elapsed, found)
+}
+
+// find の結果を使わないと最適化で消えるかもしれないので、見つかった個数を BTreeSet と比べる
+fn report(label: &str, name: &str, result: (Duration, Duration, usize), expected: usize) {
+    let (elapsed, find_elapsed, found) = result;
+    assert_eq!(found, expected);
     println!(
-        "[{}] std::collections::BTreeSet {} ms",
+        "[{}] {} {} ms (find {} ms)",
         label,
-        elapsed.as_millis()
+        name,
+        elapsed.as_millis(),
+        find_elapsed.as_millis()
     );
+}
+
+fn run<T>(label: &str, a: Vec<T>, b: Vec<T>)
+where
+    T: Clone + Ord + IntValue + FixedSize,
+{
+    let result = add_find_remove(MyBTreeSet(BTreeSet::new()), a.clone(), b.clone());
+    let expected = result.2;
+    report(label, "std::collections::BTreeSet", result, expected);
 
-    let elapsed = add_remove(SkipListSSet::new(), a.clone(), b.clone());
-    println!("[{}] SkipListSSet {} ms", label, elapsed.as_millis());
+    let result = add_find_remove(SkipListSSet::new(), a.clone(), b.clone());
+    report(label, "SkipListSSet", result, expected);
 
-    let elapsed = add_remove(Treap::new(), a.clone(), b.clone());
-    println!("[{}] Treap {} ms", label, elapsed.as_millis());
+    let result = add_find_remove(Treap::new(), a.clone(), b.clone());
+    report(label, "Treap", result, expected);
 
-    let elapsed = add_remove(ScapegoatTree::new(), a.clone(), b.clone());
-    println!("[{}] ScapegoatTree {} ms", label, elapsed.as_millis());
+    let result = add_find_remove(ScapegoatTree::new(), a.clone(), b.clone());
+    report(label, "ScapegoatTree", result, expected);
 
-    let elapsed = add_remove(BinaryTrie::new(), a.clone(), b.clone());
-    println!("[{}] BinaryTrie {} ms", label, elapsed.as_millis());
+    let result = add_find_remove(RedBlackTree::new(), a.clone(), b.clone());
+    report(label, "RedBlackTree", result, expected);
+
+    // BTree は操作のたびにノードをブロック全体ごと読み書きする
+    let store = MemoryBlockStore::new(4096);
+    let result = add_find_remove(BTree::new(store).unwrap(), a.clone(), b.clone());
+    report(label, "BTree", result, expected);
+
+    let result = add_find_remove(BinaryTrie::new(), a.clone(), b.clone());
+    report(label, "BinaryTrie", result, expected);
+
+    // XFastTrie と YFastTrie は find が O(log(w)) time
+    let result = add_find_remove(XFastTrie::new(), a.clone(), b.clone());
+    report(label, "XFastTrie", result, expected);
+
+    let result = add_find_remove(YFastTrie::new(), a.clone(), b.clone());
+    report(label, "YFastTrie", result, expected);
 }
 
 fn main() {
@@ -76,20 +125,45 @@ fn main() {
         b[i] = rng.gen_range(0..m);
     }
 
-    run("random", a, b);
+    run("random", a.clone(), b.clone());
     run("sorted", (0..m).collect(), (0..m).collect());
 
+    // BinarySearchTree は釣り合いを取らないので、昇順に追加すると O(n^2) time かかる
+    // sorted は要素数を減らして測る
+    let elapsed = add_remove(BinarySearchTree::new(), a, b);
+    println!("[random] BinarySearchTree {} ms", elapsed.as_millis());
+    for m in [m / 100, m / 10] {
+        let elapsed = add_remove(BinarySearchTree::new(), (0..m).collect(), (0..m).collect());
+        println!(
+            "[sorted, n = {}] BinarySearchTree {} ms",
+            m,
+            elapsed.as_millis()
+        );
+    }
+
     // メモリ確保・解放の時間が多くを占めている気がする……
 
-    // [random] std::collections::BTreeSet 60 ms
-    // [random] SkipListSSet 1908 ms
-    // [random] Treap 309 ms
-    // [random] ScapegoatTree 443 ms
-    // [random] BinaryTrie 508 ms
-
-    // [sorted] std::collections::BTreeSet 78 ms
-    // [sorted] SkipListSSet 1945 ms
-    // [sorted] Treap 302 ms
-    // [sorted] ScapegoatTree 8309 ms
-    // [sorted] BinaryTrie 700 ms
+    // [random] std::collections::BTreeSet 74 ms (find 37 ms)
+    // [random] SkipListSSet 706 ms (find 359 ms)
+    // [random] Treap 435 ms (find 177 ms)
+    // [random] ScapegoatTree 513 ms (find 145 ms)
+    // [random] RedBlackTree 428 ms (find 103 ms)
+    // [random] BTree 2075 ms (find 822 ms)
+    // [random] BinaryTrie 676 ms (find 280 ms)
+    // [random] XFastTrie 817 ms (find 158 ms)
+    // [random] YFastTrie 694 ms (find 308 ms)
+
+    // [sorted] std::collections::BTreeSet 53 ms (find 28 ms)
+    // [sorted] SkipListSSet 273 ms (find 63 ms)
+    // [sorted] Treap 87 ms (find 39 ms)
+    // [sorted] ScapegoatTree 765 ms (find 32 ms)
+    // [sorted] RedBlackTree 105 ms (find 25 ms)
+    // [sorted] BTree 2978 ms (find 645 ms)
+    // [sorted] BinaryTrie 298 ms (find 51 ms)
+    // [sorted] XFastTrie 419 ms (find 94 ms)
+    // [sorted] YFastTrie 155 ms (find 66 ms)
+
+    // [random] BinarySearchTree 535 ms
+    // [sorted, n = 2000] BinarySearchTree 39 ms
+    // [sorted, n = 20000] BinarySearchTree 11765 ms
 }
@@ -2,6 +2,7 @@ use std::time::{Duration, Instant};
 
 use rand::{rngs::SmallRng, Rng, SeedableRng};
 
+use array_stack::{ArrayStack, FastArrayStack};
 use interface::Stack;
 use sllist::SLList;
 
@@ -39,6 +40,14 @@ fn main() {
     let elapsed = run(SLList::new(), a.clone());
     println!("SLList {} ms", elapsed.as_millis());
 
-    // std::vec::Vec 26 ms
-    // SLList 381 ms
+    let elapsed = run(ArrayStack::new(), a.clone());
+    println!("ArrayStack {} ms", elapsed.as_millis());
+
+    let elapsed = run(FastArrayStack::new(), a.clone());
+    println!("FastArrayStack {} ms", elapsed.as_millis());
+
+    // std::vec::Vec 46 ms
+    // SLList 398 ms
+    // ArrayStack 269 ms
+    // FastArrayStack 207 ms
 }
@@ -0,0 +1,83 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+use binary_trie::IntValue;
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use chained_hash_table::ChainedHashTable;
+use interface::USet;
+use linear_hash_table::LinearHashTable;
+
+struct MyHashSet<T>(HashSet<T>);
+impl<T> USet<T> for MyHashSet<T>
+where
+    T: Hash + Eq,
+{
+    fn size(&self) -> usize {
+        unreachable!()
+    }
+    fn add(&mut self, x: T) -> bool {
+        self.0.insert(x)
+    }
+    fn remove(&mut self, x: &T) -> bool {
+        self.0.remove(x)
+    }
+    fn find(&self, _: &T) -> Option<&T> {
+        unreachable!()
+    }
+}
+
+fn add_remove<T>(mut set: impl USet<T>, a: Vec<T>, b: Vec<T>) -> Duration {
+    let now = Instant::now();
+    for a in a {
+        set.add(a);
+    }
+    for b in b {
+        set.remove(&b);
+    }
+    now.elapsed()
+}
+
+fn run<T>(label: &str, a: Vec<T>, b: Vec<T>)
+where
+    T: Clone + Hash + Eq + IntValue,
+{
+    let elapsed = add_remove(MyHashSet(HashSet::new()), a.clone(), b.clone());
+    println!(
+        "[{}] std::collections::HashSet {} ms",
+        label,
+        elapsed.as_millis()
+    );
+
+    let elapsed = add_remove(ChainedHashTable::new(), a.clone(), b.clone());
+    println!("[{}] ChainedHashTable {} ms", label, elapsed.as_millis());
+
+    let elapsed = add_remove(LinearHashTable::new(), a.clone(), b.clone());
+    println!("[{}] LinearHashTable {} ms", label, elapsed.as_millis());
+}
+
+fn main() {
+    let mut rng = SmallRng::seed_from_u64(1223334);
+
+    let n = 200_000;
+    let m = 200_000_u32;
+
+    let mut a = vec![0; n];
+    let mut b = vec![0; n];
+    for i in 0..n {
+        a[i] = rng.gen_range(0..m);
+        b[i] = rng.gen_range(0..m);
+    }
+
+    run("random", a, b);
+    run("sorted", (0..m).collect(), (0..m).collect());
+
+    // [random] std::collections::HashSet 19 ms
+    // [random] ChainedHashTable 56 ms
+    // [random] LinearHashTable 20 ms
+
+    // [sorted] std::collections::HashSet 17 ms
+    // [sorted] ChainedHashTable 137 ms
+    // [sorted] LinearHashTable 23 ms
+}
@@ -0,0 +1,188 @@
+use interface::PriorityQueue;
+
+// a[i] の子は a[2i+1] と a[2i+2]、親は a[(i-1)/2]
+// 親は子以下の値を持つ
+pub struct BinaryHeap<T> {
+    a: Box<[Option<T>]>,
+    n: usize,
+}
+
+impl<T: Ord> BinaryHeap<T> {
+    pub fn new() -> Self {
+        Self {
+            a: Self::alloc_array(1),
+            n: 0,
+        }
+    }
+
+    // 葉でない頂点を下から順に trickle_down する
+    // O(n) time
+    pub fn from_vec(v: Vec<T>) -> Self {
+        let n = v.len();
+        let mut a: Vec<Option<T>> = v.into_iter().map(Some).collect();
+        if a.is_empty() {
+            a.push(None);
+        }
+        let mut heap = Self {
+            a: a.into_boxed_slice(),
+            n,
+        };
+        for i in (0..n / 2).rev() {
+            heap.trickle_down(i);
+        }
+        heap
+    }
+
+    fn alloc_array(len: usize) -> Box<[Option<T>]> {
+        (0..len).map(|_| None).collect()
+    }
+
+    // 配列の長さを max(2n, 1) にする
+    // O(n) time
+    fn resize(&mut self) {
+        let mut b = Self::alloc_array(1.max(self.n * 2));
+        for (i, y) in b.iter_mut().take(self.n).enumerate() {
+            *y = self.a[i].take();
+        }
+        self.a = b;
+    }
+
+    // a[0..n] はすべて Some なので Option<T> のまま比べてよい
+    // O(log n) time
+    fn bubble_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let p = (i - 1) / 2;
+            if self.a[i] >= self.a[p] {
+                break;
+            }
+            self.a.swap(i, p);
+            i = p;
+        }
+    }
+
+    // O(log n) time
+    fn trickle_down(&mut self, mut i: usize) {
+        loop {
+            let l = i * 2 + 1;
+            let r = i * 2 + 2;
+            let mut j = i;
+            if r < self.n && self.a[r] < self.a[j] {
+                j = r;
+            }
+            if l < self.n && self.a[l] < self.a[j] {
+                j = l;
+            }
+            if j == i {
+                break;
+            }
+            self.a.swap(i, j);
+            i = j;
+        }
+    }
+}
+
+impl<T: Ord> Default for BinaryHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> PriorityQueue<T> for BinaryHeap<T> {
+    fn size(&self) -> usize {
+        self.n
+    }
+
+    // amortized O(log n) time
+    fn add(&mut self, x: T) {
+        if self.n + 1 > self.a.len() {
+            self.resize();
+        }
+        self.a[self.n] = Some(x);
+        self.n += 1;
+        self.bubble_up(self.n - 1);
+    }
+
+    // amortized O(log n) time
+    fn remove_min(&mut self) -> Option<T> {
+        if self.n == 0 {
+            return None;
+        }
+        let x = self.a[0].take();
+        self.a.swap(0, self.n - 1);
+        self.n -= 1;
+        self.trickle_down(0);
+        if self.a.len() >= self.n * 3 {
+            self.resize();
+        }
+        x
+    }
+
+    // O(1) time
+    fn peek_min(&self) -> Option<&T> {
+        self.a[0].as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BinaryHeap;
+    use interface::PriorityQueue;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+    use std::cmp::Reverse;
+
+    #[test]
+    fn test_add_remove() {
+        let mut heap = BinaryHeap::new();
+        assert_eq!(heap.peek_min(), None);
+        assert_eq!(heap.remove_min(), None);
+        heap.add(3);
+        heap.add(1);
+        heap.add(2);
+        assert_eq!(heap.peek_min(), Some(&1));
+        assert_eq!(heap.remove_min(), Some(1));
+        assert_eq!(heap.remove_min(), Some(2));
+        heap.add(0);
+        assert_eq!(heap.remove_min(), Some(0));
+        assert_eq!(heap.remove_min(), Some(3));
+        assert_eq!(heap.remove_min(), None);
+        assert_eq!(heap.size(), 0);
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut heap = BinaryHeap::new();
+        let mut std_heap = std::collections::BinaryHeap::new();
+        for _ in 0..1000 {
+            if rng.gen_bool(0.6) {
+                let x = rng.gen_range(0..100);
+                heap.add(x);
+                std_heap.push(Reverse(x));
+            } else {
+                assert_eq!(heap.remove_min(), std_heap.pop().map(|Reverse(x)| x));
+            }
+            assert_eq!(heap.size(), std_heap.len());
+            assert_eq!(heap.peek_min(), std_heap.peek().map(|Reverse(x)| x));
+            assert!(heap.a.len() <= (heap.size() * 3).max(1));
+        }
+    }
+
+    #[test]
+    fn test_from_vec() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for n in 0..50 {
+            let v: Vec<u32> = (0..n).map(|_| rng.gen_range(0..10)).collect();
+            let mut heap = BinaryHeap::from_vec(v.clone());
+            for i in 1..n {
+                assert!(heap.a[(i - 1) / 2] <= heap.a[i]);
+            }
+            let mut sorted = v;
+            sorted.sort();
+            let mut removed = Vec::new();
+            while let Some(x) = heap.remove_min() {
+                removed.push(x);
+            }
+            assert_eq!(removed, sorted);
+        }
+    }
+}
@@ -0,0 +1,583 @@
+// 生ポインタを受け取る関数は unsafe fn にしている
+// どれもこの crate の Node からなる木のノード (または NULL) を渡す前提
+#![allow(clippy::missing_safety_doc)]
+
+use std::{
+    cmp::{self, Ordering},
+    ptr,
+};
+
+use interface::SSet;
+
+// Treap や ScapegoatTree もこのノードと関数を使う
+// data には木ごとに必要な情報を持たせる (Treap の優先度など)
+pub struct Node<T, D = ()> {
+    pub x: T,
+    pub data: D,
+    pub size: usize, // このノードを根とする部分木のノード数
+    pub parent: *mut Node<T, D>,
+    pub left: *mut Node<T, D>,
+    pub right: *mut Node<T, D>,
+}
+
+impl<T, D> Node<T, D> {
+    pub fn new(x: T, data: D) -> Self {
+        Self {
+            x,
+            data,
+            size: 1,
+            parent: ptr::null_mut(),
+            left: ptr::null_mut(),
+            right: ptr::null_mut(),
+        }
+    }
+}
+
+// u を根とする部分木のノード数
+// O(1) time
+pub unsafe fn size_u<T, D>(u: *mut Node<T, D>) -> usize {
+    if u.is_null() {
+        0
+    } else {
+        (*u).size
+    }
+}
+
+// 子の size から u の size を計算しなおす
+// O(1) time
+pub unsafe fn update_size<T, D>(u: *mut Node<T, D>) {
+    (*u).size = 1 + size_u((*u).left) + size_u((*u).right);
+}
+
+// u の深さ
+// O(depth) time
+pub unsafe fn depth<T, D>(u: *mut Node<T, D>) -> usize {
+    let mut d = 0;
+    let mut v = (*u).parent;
+    while !v.is_null() {
+        d += 1;
+        v = (*v).parent;
+    }
+    d
+}
+
+// u を根とする部分木で要素が最小のノードを返す
+// O(height) time
+pub unsafe fn first_node<T, D>(u: *mut Node<T, D>) -> *mut Node<T, D> {
+    let mut u = u;
+    if u.is_null() {
+        return u;
+    }
+    while !(*u).left.is_null() {
+        u = (*u).left;
+    }
+    u
+}
+
+// u の次に大きい要素をもつノードを返す
+// amortized O(1) time
+pub unsafe fn next_node<T, D>(u: *mut Node<T, D>) -> *mut Node<T, D> {
+    let right = (*u).right;
+    if !right.is_null() {
+        return first_node(right);
+    }
+    // 右の子として親を辿れるあいだは上に登る
+    let mut u = u;
+    loop {
+        let p = (*u).parent;
+        if p.is_null() || (*p).left == u {
+            return p;
+        }
+        u = p;
+    }
+}
+
+// - x に等しい要素を持つノードがあればそのノードを返す
+// - そうでなければ、x を探索する経路で最後に通ったノードを返す
+//   - ノードがもつ要素は x より小さいこともあれば大きいこともある
+// O(height) time
+pub unsafe fn find_last<T, D>(root: *mut Node<T, D>, x: &T) -> *mut Node<T, D>
+where
+    T: cmp::Ord,
+{
+    let mut w = root;
+    let mut prev = ptr::null_mut();
+    while !w.is_null() {
+        prev = w;
+        match x.cmp(&(*w).x) {
+            Ordering::Less => {
+                w = (*w).left;
+            }
+            Ordering::Greater => {
+                w = (*w).right;
+            }
+            Ordering::Equal => {
+                return w;
+            }
+        }
+    }
+    prev
+}
+
+// pred(y) = false となる要素をもつノードのうち最初のものを返す
+// pred は小さい要素で true, 大きい要素で false になっている必要がある
+// O(height) time
+pub unsafe fn partition_point_node<T, D, F>(root: *mut Node<T, D>, pred: F) -> *mut Node<T, D>
+where
+    F: Fn(&T) -> bool,
+{
+    let mut w = root;
+    let mut z = ptr::null_mut();
+    while !w.is_null() {
+        if pred(&(*w).x) {
+            w = (*w).right;
+        } else {
+            z = w;
+            w = (*w).left;
+        }
+    }
+    z
+}
+
+// pred(y) = false となる最初の要素 y が eq(y) = true ならそのノードを返す
+// なければ NULL を返す
+// キーで比較する Map から使う
+// O(height) time
+pub unsafe fn find_node_by<T, D, F, G>(root: *mut Node<T, D>, pred: F, eq: G) -> *mut Node<T, D>
+where
+    F: Fn(&T) -> bool,
+    G: Fn(&T) -> bool,
+{
+    let u = partition_point_node(root, pred);
+    if !u.is_null() && eq(&(*u).x) {
+        u
+    } else {
+        ptr::null_mut()
+    }
+}
+
+// pred(y) = true となる要素をもつノードのうち最後のものを返す
+// pred は小さい要素で true, 大きい要素で false になっている必要がある
+// O(height) time
+pub unsafe fn find_pred_node_by<T, D, F>(root: *mut Node<T, D>, pred: F) -> *mut Node<T, D>
+where
+    F: Fn(&T) -> bool,
+{
+    let mut w = root;
+    let mut z = ptr::null_mut();
+    while !w.is_null() {
+        if pred(&(*w).x) {
+            z = w;
+            w = (*w).right;
+        } else {
+            w = (*w).left;
+        }
+    }
+    z
+}
+
+// x より小さい要素の個数を返す
+// O(height) time
+pub unsafe fn rank<T, D>(root: *mut Node<T, D>, x: &T) -> usize
+where
+    T: cmp::Ord,
+{
+    let mut w = root;
+    let mut k = 0;
+    while !w.is_null() {
+        if x <= &(*w).x {
+            w = (*w).left;
+        } else {
+            k += size_u((*w).left) + 1;
+            w = (*w).right;
+        }
+    }
+    k
+}
+
+// 小さいほうから k 番目 (0-indexed) の要素をもつノードを返す
+// O(height) time
+pub unsafe fn select<T, D>(root: *mut Node<T, D>, k: usize) -> *mut Node<T, D> {
+    let mut w = root;
+    let mut k = k;
+    while !w.is_null() {
+        let l = size_u((*w).left);
+        match k.cmp(&l) {
+            Ordering::Less => {
+                w = (*w).left;
+            }
+            Ordering::Equal => {
+                return w;
+            }
+            Ordering::Greater => {
+                k -= l + 1;
+                w = (*w).right;
+            }
+        }
+    }
+    ptr::null_mut()
+}
+
+// u を根とする部分木の高さ
+// O(n) time
+pub unsafe fn height<T, D>(u: *mut Node<T, D>) -> usize {
+    let mut max_depth = 0;
+    let mut stack = vec![(u, 0)];
+    while let Some((u, d)) = stack.pop() {
+        if u.is_null() {
+            continue;
+        }
+        max_depth = cmp::max(max_depth, d);
+        stack.push(((*u).left, d + 1));
+        stack.push(((*u).right, d + 1));
+    }
+    max_depth
+}
+
+// u を p の子にする。p が NULL なら u を根にする
+// p は find_last(root, &u.x) で見つけたノードで、u.x に等しい要素を持たない
+// p から根までのノードの size を 1 増やす
+// O(height) time
+pub unsafe fn add_child<T, D>(root: &mut *mut Node<T, D>, p: *mut Node<T, D>, u: *mut Node<T, D>)
+where
+    T: cmp::Ord,
+{
+    (*u).parent = p;
+    if p.is_null() {
+        debug_assert!(root.is_null());
+        *root = u;
+        return;
+    }
+    match (*u).x.cmp(&(*p).x) {
+        Ordering::Less => {
+            debug_assert!((*p).left.is_null());
+            (*p).left = u;
+        }
+        Ordering::Greater => {
+            debug_assert!((*p).right.is_null());
+            (*p).right = u;
+        }
+        Ordering::Equal => {
+            unreachable!();
+        }
+    }
+    let mut v = p;
+    while !v.is_null() {
+        (*v).size += 1;
+        v = (*v).parent;
+    }
+}
+
+// u の位置を v に置き換える (v は NULL でもよい)
+// size は変えない
+// O(1) time
+unsafe fn replace_child<T, D>(root: &mut *mut Node<T, D>, u: *mut Node<T, D>, v: *mut Node<T, D>) {
+    let p = (*u).parent;
+    if !v.is_null() {
+        (*v).parent = p;
+    }
+    if p.is_null() {
+        *root = v;
+    } else if (*p).left == u {
+        (*p).left = v;
+    } else {
+        debug_assert_eq!((*p).right, u);
+        (*p).right = v;
+    }
+}
+
+// u を木から外す。u のメモリは呼び出し側で解放する
+// 子が 2 つあるときは右の部分木の最小のノード w を u の位置に持ってくる
+// O(height) time
+pub unsafe fn remove_node<T, D>(root: &mut *mut Node<T, D>, u: *mut Node<T, D>) {
+    let left_u = (*u).left;
+    let right_u = (*u).right;
+    if left_u.is_null() || right_u.is_null() {
+        // u の親から根までのノードは要素がひとつ減る
+        let mut v = (*u).parent;
+        while !v.is_null() {
+            (*v).size -= 1;
+            v = (*v).parent;
+        }
+        let child = if left_u.is_null() { right_u } else { left_u };
+        replace_child(root, u, child);
+        return;
+    }
+    // w.x が u.x より大きい最小の値
+    let w = first_node(right_u);
+    // w の親から根までのノードは要素がひとつ減る
+    let mut v = (*w).parent;
+    while !v.is_null() {
+        (*v).size -= 1;
+        v = (*v).parent;
+    }
+    // w を外して、w の右の子を w の位置に持っていく
+    replace_child(root, w, (*w).right);
+    // w を u の位置に持っていく
+    // w = right_u の場合があるので u.right は読みなおす
+    (*w).left = (*u).left;
+    (*w).right = (*u).right;
+    (*w).size = (*u).size;
+    (*(*w).left).parent = w;
+    if !(*w).right.is_null() {
+        (*(*w).right).parent = w;
+    }
+    replace_child(root, u, w);
+}
+
+// O(1) time
+pub unsafe fn rotate_right<T, D>(root: &mut *mut Node<T, D>, u: *mut Node<T, D>) {
+    //         u                      w
+    //         |                      |
+    //     +---+---+              +---+---+
+    //     |       |              |       |
+    //     w       c      ->      a       u
+    //     |                              |
+    // +---+---+                      +---+---+
+    // |       |                      |       |
+    // a       b                      b       c
+    let w = (*u).left;
+    debug_assert!(!w.is_null());
+    replace_child(root, u, w);
+    let b = (*w).right;
+    (*u).left = b;
+    if !b.is_null() {
+        (*b).parent = u;
+    }
+    (*w).right = u;
+    (*u).parent = w;
+    update_size(u);
+    update_size(w);
+}
+
+// O(1) time
+pub unsafe fn rotate_left<T, D>(root: &mut *mut Node<T, D>, u: *mut Node<T, D>) {
+    //      u                         w
+    //      |                         |
+    //  +---+---+                 +---+---+
+    //  |       |                 |       |
+    //  a       w        ->       u       c
+    //          |                 |
+    //      +---+---+         +---+---+
+    //      |       |         |       |
+    //      b       c         a       b
+    let w = (*u).right;
+    debug_assert!(!w.is_null());
+    replace_child(root, u, w);
+    let b = (*w).left;
+    (*u).right = b;
+    if !b.is_null() {
+        (*b).parent = u;
+    }
+    (*w).left = u;
+    (*u).parent = w;
+    update_size(u);
+    update_size(w);
+}
+
+// u を根とする部分木のノードをすべて解放する
+// O(n) time
+pub unsafe fn drop_tree<T, D>(u: *mut Node<T, D>) {
+    let mut stack = vec![u];
+    while let Some(u) = stack.pop() {
+        if u.is_null() {
+            continue;
+        }
+        let u = Box::from_raw(u);
+        stack.push(u.left);
+        stack.push(u.right);
+    }
+}
+
+// 二分探索木を使う crate のテストから使う
+#[cfg(any(test, feature = "testing"))]
+pub mod testing {
+    use super::Node;
+
+    // root を根とする木の二分探索木の性質, parent, size が正しいか調べて、ノード数を返す
+    // 木ごとの性質は check で各ノードについて調べる
+    pub unsafe fn validate<T: Ord, D>(
+        root: *mut Node<T, D>,
+        mut check: impl FnMut(&Node<T, D>),
+    ) -> usize {
+        unsafe fn dfs<T: Ord, D>(u: *mut Node<T, D>, check: &mut impl FnMut(&Node<T, D>)) -> usize {
+            if u.is_null() {
+                return 0;
+            }
+            let node = &*u;
+            check(node);
+            for &c in &[node.left, node.right] {
+                if !c.is_null() {
+                    assert_eq!((*c).parent, u);
+                }
+            }
+            if !node.left.is_null() {
+                assert!((*node.left).x < node.x);
+            }
+            if !node.right.is_null() {
+                assert!(node.x < (*node.right).x);
+            }
+            let size = 1 + dfs(node.left, check) + dfs(node.right, check);
+            assert_eq!(node.size, size);
+            size
+        }
+        if !root.is_null() {
+            assert!((*root).parent.is_null());
+        }
+        dfs(root, &mut check)
+    }
+}
+
+// 釣り合いを取らない二分探索木
+// 要素を昇順に追加すると一直線になる
+pub struct BinarySearchTree<T> {
+    n: usize,
+    root: *mut Node<T>,
+}
+
+impl<T> BinarySearchTree<T> {
+    pub fn new() -> Self {
+        Self {
+            n: 0,
+            root: ptr::null_mut(),
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        unsafe { height(self.root) }
+    }
+}
+
+impl<T> Default for BinarySearchTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SSet<T> for BinarySearchTree<T>
+where
+    T: cmp::Ord,
+{
+    // O(1) time
+    fn size(&self) -> usize {
+        self.n
+    }
+
+    // O(height) time
+    fn add(&mut self, x: T) -> bool {
+        let p = unsafe { find_last(self.root, &x) };
+        if !p.is_null() && unsafe { &*p }.x == x {
+            return false;
+        }
+        let u = Box::into_raw(Box::new(Node::new(x, ())));
+        unsafe { add_child(&mut self.root, p, u) };
+        self.n += 1;
+        true
+    }
+
+    // O(height) time
+    fn remove(&mut self, x: &T) -> bool {
+        let u = unsafe { find_last(self.root, x) };
+        if u.is_null() || unsafe { &*u }.x != *x {
+            return false;
+        }
+        unsafe { remove_node(&mut self.root, u) };
+        drop(unsafe { Box::from_raw(u) });
+        self.n -= 1;
+        true
+    }
+
+    // O(height) time
+    fn find(&self, x: &T) -> Option<&T> {
+        let u = unsafe { partition_point_node(self.root, |y| y < x) };
+        unsafe { u.as_ref() }.map(|u| &u.x)
+    }
+}
+
+impl<T> Drop for BinarySearchTree<T> {
+    fn drop(&mut self) {
+        unsafe { drop_tree(self.root) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rotate_right, testing, BinarySearchTree, Node};
+    use interface::SSet;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+    use std::collections::BTreeSet;
+    use std::ptr;
+
+    // 二分探索木の性質, parent, size が正しいか調べる
+    fn validate<T: Ord>(tree: &BinarySearchTree<T>) {
+        assert_eq!(unsafe { testing::validate(tree.root, |_| ()) }, tree.size());
+    }
+
+    #[test]
+    fn test_rotate() {
+        let node = |x, left: *mut Node<char>, right: *mut Node<char>| {
+            let mut u = Node::new(x, ());
+            u.left = left;
+            u.right = right;
+            Box::into_raw(Box::new(u))
+        };
+        let a = node('a', ptr::null_mut(), ptr::null_mut());
+        let b = node('b', ptr::null_mut(), ptr::null_mut());
+        let c = node('c', ptr::null_mut(), ptr::null_mut());
+        let w = node('w', a, b);
+        let u = node('u', w, c);
+        let r = node('r', u, ptr::null_mut());
+        unsafe {
+            (*a).parent = w;
+            (*b).parent = w;
+            (*c).parent = u;
+            (*w).parent = u;
+            (*u).parent = r;
+        };
+        let mut root = r;
+
+        unsafe { rotate_right(&mut root, u) };
+
+        assert_eq!(root, r);
+        assert_eq!(unsafe { (*a).parent }, w);
+        assert_eq!(unsafe { (*b).parent }, u);
+        assert_eq!(unsafe { (*c).parent }, u);
+        assert_eq!(unsafe { (*w).parent }, r);
+        assert_eq!(unsafe { (*w).left }, a);
+        assert_eq!(unsafe { (*w).right }, u);
+        assert_eq!(unsafe { (*u).parent }, w);
+        assert_eq!(unsafe { (*u).left }, b);
+        assert_eq!(unsafe { (*u).right }, c);
+        assert_eq!(unsafe { (*r).left }, w);
+        unsafe { super::drop_tree(root) };
+    }
+
+    #[test]
+    fn test_sorted() {
+        let mut tree = BinarySearchTree::new();
+        for x in 0..100 {
+            assert!(tree.add(x));
+        }
+        assert!(!tree.add(0));
+        assert_eq!(tree.height(), 99);
+        validate(&tree);
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut tree = BinarySearchTree::new();
+        let mut btree_set = BTreeSet::new();
+        for _ in 0..1000 {
+            let x = rng.gen_range(0..100);
+            if rng.gen_bool(0.5) {
+                assert_eq!(tree.add(x), btree_set.insert(x));
+            } else {
+                assert_eq!(tree.remove(&x), btree_set.remove(&x));
+            }
+            let x = rng.gen_range(0..110);
+            assert_eq!(tree.find(&x), btree_set.range(x..).next());
+            validate(&tree);
+        }
+    }
+}
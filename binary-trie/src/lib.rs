@@ -1,8 +1,18 @@
-use std::{alloc, ptr};
+// PrefixIndex::find_prefix は生ポインタを受け取るので unsafe fn にしている
+// この BinaryTrie の根と、解放されていないノードだけを渡す前提
+#![allow(clippy::missing_safety_doc)]
 
-use interface::SSet;
+use std::{
+    alloc,
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+    ptr,
+};
 
-struct Node<T> {
+use interface::{NavigableSSet, Pair, PairMap, PairSet, RangeSSet, SSet};
+
+// XFastTrie も PrefixIndex からこのノードへのポインタを持つ
+pub struct Node<T> {
     // 葉 ⇒ x = Some(.), child = [NULL, NULL]
     // 葉以外 ⇒ x = None, prev = next = NULL
     x: Option<T>,
@@ -13,20 +23,64 @@ struct Node<T> {
     jump: *mut Node<T>,
 }
 
-pub struct BinaryTrie<T> {
+pub struct BinaryTrie<T, I = NoIndex> {
     n: usize,
     root: *mut Node<T>,
     dummy: *mut Node<T>,
+    index: I,
+}
+
+// 根から ix への経路を辿るときに、経路上で最も深いノードを探す方法
+// 根以外のノードを作ったり消したりするたびに呼ばれる
+// XFastTrie は各深さのノードをハッシュテーブルに入れて、深さを二分探索する
+pub trait PrefixIndex<T> {
+    // ix への経路上で最も深いノードを返す
+    // root は根で、ix への経路上のノードは解放されていない
+    unsafe fn find_prefix(&self, root: *mut Node<T>, ix: u64) -> *mut Node<T>;
+    // 上位 i ビットが prefix の経路に深さ i のノード u を作った
+    fn insert(&mut self, i: u32, prefix: u64, u: *mut Node<T>);
+    // 上位 i ビットが prefix の経路にある深さ i のノードを消した
+    fn remove(&mut self, i: u32, prefix: u64);
+}
+
+// 何も持たずに根から 1 段ずつ辿る
+#[derive(Default)]
+pub struct NoIndex;
+
+impl<T> PrefixIndex<T> for NoIndex {
+    // O(w) time
+    unsafe fn find_prefix(&self, root: *mut Node<T>, ix: u64) -> *mut Node<T> {
+        let w = u64::BITS;
+        let mut u = root;
+        for i in 0..w {
+            let b = (ix >> (w - i - 1) & 1) as usize;
+            let child = (*u).child[b];
+            if child.is_null() {
+                break;
+            }
+            u = child;
+        }
+        u
+    }
+
+    fn insert(&mut self, _: u32, _: u64, _: *mut Node<T>) {}
+
+    fn remove(&mut self, _: u32, _: u64) {}
 }
 
 pub trait IntValue {
+    // int_value は 2^BITS 未満
+    const BITS: u32 = u64::BITS;
+
     fn int_value(&self) -> u64;
 }
 
 macro_rules! impl_int_value {
-     ($($t:ty),+) => {
+     ($($t:ty: $bits:expr),+) => {
          $(
             impl IntValue for $t {
+                const BITS: u32 = $bits;
+
                 fn int_value(&self) -> u64 {
                     u64::from(*self)
                 }
@@ -35,10 +89,26 @@ macro_rules! impl_int_value {
      };
 }
 
-impl_int_value!(bool, char, u8, u16, u32, u64);
+// char は 0x10FFFF 以下
+impl_int_value!(bool: 1, char: 21, u8: u8::BITS, u16: u16::BITS, u32: u32::BITS, u64: u64::BITS);
 
 impl<T> BinaryTrie<T> {
     pub fn new() -> Self {
+        Self::with_index(NoIndex)
+    }
+}
+
+impl<T> Default for BinaryTrie<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, I> BinaryTrie<T, I>
+where
+    I: PrefixIndex<T>,
+{
+    pub fn with_index(index: I) -> Self {
         let dummy = Box::into_raw(Box::new(Node {
             x: None,
             child: [ptr::null_mut(), ptr::null_mut()],
@@ -61,19 +131,74 @@ impl<T> BinaryTrie<T> {
                 jump: dummy,
             })),
             dummy,
+            index,
+        }
+    }
+
+    pub fn index(&self) -> &I {
+        &self.index
+    }
+
+    // ix 以上で最小の要素をもつ葉を返す
+    // そのような葉がなければ dummy を返す
+    // find_prefix の時間 + O(1) time
+    fn find_node(&self, ix: u64) -> *mut Node<T> {
+        if self.n == 0 {
+            return self.dummy;
+        }
+        let u = unsafe { self.index.find_prefix(self.root, ix) };
+        self.successor_leaf(u)
+    }
+
+    // ix への経路を辿って最後に着いたノード u から、ix 以上で最小の要素をもつ葉を返す
+    // O(1) time
+    fn successor_leaf(&self, u: *mut Node<T>) -> *mut Node<T> {
+        let left = unsafe { &*u }.child[0];
+        let right = unsafe { &*u }.child[1];
+        if left.is_null() && right.is_null() {
+            // 葉
+            debug_assert!(unsafe { &*u }.x.is_some());
+            u
+        } else {
+            let v = unsafe { &*u }.jump;
+            if left.is_null() {
+                v
+            } else if right.is_null() {
+                unsafe { &*v }.next
+            } else {
+                unreachable!()
+            }
+        }
+    }
+
+    // ix より大きい最小の要素をもつ葉を返す
+    // そのような葉がなければ dummy を返す
+    // O(w) time
+    fn find_node_gt(&self, ix: u64) -> *mut Node<T> {
+        match ix.checked_add(1) {
+            Some(ix) => self.find_node(ix),
+            None => self.dummy,
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            u: unsafe { &*self.dummy }.next,
+            dummy: self.dummy,
+            _marker: PhantomData,
         }
     }
 }
 
-impl<T> SSet<T> for BinaryTrie<T>
+impl<T, I> BinaryTrie<T, I>
 where
     T: IntValue,
+    I: PrefixIndex<T>,
 {
-    fn size(&self) -> usize {
-        self.n
-    }
-
-    fn add(&mut self, x: T) -> bool {
+    // x を追加して、x を持つ葉を返す
+    // x に等しい要素がすでにあるときは何もせずに NULL を返す
+    // O(w) time
+    fn add_node(&mut self, x: T) -> *mut Node<T> {
         let w = u64::BITS;
         let ix = x.int_value();
         let mut u = self.root;
@@ -106,6 +231,7 @@ where
                         jump: ptr::null_mut(),
                     }));
                     unsafe { (*u).child[b] = child };
+                    self.index.insert(j + 1, ix >> (w - j - 1), child);
                     u = child;
                 }
 
@@ -142,30 +268,31 @@ where
                 }
 
                 self.n += 1;
-                return true;
+                return u;
             }
         }
 
         // 葉まで辿りついたのですでに x が BinaryTrie に含まれていた
-        false
+        ptr::null_mut()
     }
 
-    fn remove(&mut self, x: &T) -> bool {
+    // 整数値が ix の要素を取り除いて返す
+    // O(w) time
+    fn remove_ix(&mut self, ix: u64) -> Option<T> {
         let w = u64::BITS;
-        let ix = x.int_value();
         let mut u = self.root;
 
         for i in 0..w {
             let b = (ix >> (w - i - 1) & 1) as usize;
             let child = unsafe { &*u }.child[b];
             if child == ptr::null_mut() {
-                return false;
+                return None;
             }
             u = child;
         }
 
-        debug_assert!(unsafe { &*u }.x.is_some());
-        // debug_assert_eq!(unsafe {&*u}.x.as_ref(), Some(x));
+        let x = unsafe { (*u).x.take() };
+        debug_assert!(x.is_some());
 
         // u を葉の連結リストから除く
         unsafe { (*(*u).prev).next = (*u).next };
@@ -178,6 +305,7 @@ where
             unsafe { ptr::drop_in_place((*v).child[b]) };
             unsafe { alloc::dealloc((*v).child[b] as *mut u8, alloc::Layout::new::<Node<T>>()) };
             unsafe { (*v).child[b] = ptr::null_mut() };
+            self.index.remove(i + 1, ix >> (w - i - 1));
 
             // 左 or 右の子があるので v は消さない
             if unsafe { &*v }.child[1 - b] != ptr::null_mut() {
@@ -189,7 +317,8 @@ where
                 for j in (0..i).rev() {
                     let b = (ix >> (w - j - 1) & 1) as usize;
                     if unsafe { &*v }.jump == u {
-                        unsafe { (*v).jump = if b == 0 { next } else { prev } };
+                        // 子が右だけなら jump は部分木の最小の葉、左だけなら最大の葉を指している
+                        unsafe { (*v).jump = if b == 0 { prev } else { next } };
                     }
                     v = unsafe { &*v }.parent;
                 }
@@ -197,63 +326,294 @@ where
                 break;
             }
         }
+        if unsafe { &*self.root }.child == [ptr::null_mut(), ptr::null_mut()] {
+            // 空になったので root.jump が消した葉を指したままにならないようにする
+            unsafe { (*self.root).jump = self.dummy };
+        }
+
+        self.n -= 1;
+        x
+    }
+}
+
+impl<T, I> SSet<T> for BinaryTrie<T, I>
+where
+    T: IntValue,
+    I: PrefixIndex<T>,
+{
+    fn size(&self) -> usize {
+        self.n
+    }
 
-        true
+    fn add(&mut self, x: T) -> bool {
+        !self.add_node(x).is_null()
+    }
+
+    fn remove(&mut self, x: &T) -> bool {
+        self.remove_ix(x.int_value()).is_some()
     }
 
     fn find(&self, x: &T) -> Option<&T> {
-        if self.n == 0 {
+        let u = self.find_node(x.int_value());
+        // u = dummy なら None
+        unsafe { &*u }.x.as_ref()
+    }
+}
+
+impl<'a, T, I> RangeSSet<'a, T> for BinaryTrie<T, I>
+where
+    T: IntValue + 'a,
+    I: PrefixIndex<T>,
+{
+    type Range = Range<'a, T>;
+
+    fn range<R>(&'a self, range: R) -> Self::Range
+    where
+        R: RangeBounds<T>,
+    {
+        // [u, end) を返す
+        let u = match range.start_bound() {
+            Bound::Included(x) => self.find_node(x.int_value()),
+            Bound::Excluded(x) => self.find_node_gt(x.int_value()),
+            Bound::Unbounded => unsafe { &*self.dummy }.next,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(x) => self.find_node_gt(x.int_value()),
+            Bound::Excluded(x) => self.find_node(x.int_value()),
+            Bound::Unbounded => self.dummy,
+        };
+        if u != self.dummy && end != self.dummy {
+            let iu = unsafe { &*u }.x.as_ref().unwrap().int_value();
+            let iend = unsafe { &*end }.x.as_ref().unwrap().int_value();
+            if iu >= iend {
+                // 始点が終点より後ろにあるので空
+                return Range {
+                    u: end,
+                    end,
+                    _marker: PhantomData,
+                };
+            }
+        }
+        Range {
+            u,
+            end,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, I> NavigableSSet<T> for BinaryTrie<T, I>
+where
+    T: IntValue,
+    I: PrefixIndex<T>,
+{
+    // O(w) time
+    fn find_gt(&self, x: &T) -> Option<&T> {
+        let u = self.find_node_gt(x.int_value());
+        unsafe { &*u }.x.as_ref()
+    }
+
+    // O(w) time
+    fn find_le(&self, x: &T) -> Option<&T> {
+        let u = self.find_node_gt(x.int_value());
+        // u = dummy のとき prev は最大の要素をもつ葉
+        let u = unsafe { &*u }.prev;
+        unsafe { &*u }.x.as_ref()
+    }
+
+    // O(w) time
+    fn find_lt(&self, x: &T) -> Option<&T> {
+        let u = self.find_node(x.int_value());
+        let u = unsafe { &*u }.prev;
+        unsafe { &*u }.x.as_ref()
+    }
+
+    // O(1) time
+    fn first(&self) -> Option<&T> {
+        let u = unsafe { &*self.dummy }.next;
+        unsafe { &*u }.x.as_ref()
+    }
+
+    // O(1) time
+    fn last(&self) -> Option<&T> {
+        let u = unsafe { &*self.dummy }.prev;
+        unsafe { &*u }.x.as_ref()
+    }
+}
+
+pub struct Range<'a, T> {
+    u: *mut Node<T>,
+    end: *mut Node<T>,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for Range<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.u == self.end {
             return None;
         }
+        let u = unsafe { &*self.u };
+        self.u = u.next;
+        let x = u.x.as_ref();
+        debug_assert!(x.is_some());
+        x
+    }
+}
 
-        let w = u64::BITS;
-        let ix = x.int_value();
-        let mut u = self.root;
-        for i in 0..w {
-            let b = (ix >> (w - i - 1) & 1) as usize;
-            let child = unsafe { &*u }.child[b];
-            if child == ptr::null_mut() {
-                break;
-            }
-            u = child;
+pub struct Iter<'a, T> {
+    u: *mut Node<T>,
+    dummy: *mut Node<T>,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.u == self.dummy {
+            return None;
         }
-        let left = unsafe { &*u }.child[0];
-        let right = unsafe { &*u }.child[1];
-        if left == ptr::null_mut() && right == ptr::null_mut() {
-            // 葉
-            let x = unsafe { &*u }.x.as_ref();
-            debug_assert!(x.is_some());
-            x
-        } else {
-            let v = unsafe { &*u }.jump;
-            if left == ptr::null_mut() {
-                let x = unsafe { &*v }.x.as_ref();
-                if v == self.dummy {
-                    debug_assert!(x.is_none());
-                } else {
-                    debug_assert!(x.is_some());
-                }
-                x
-            } else if right == ptr::null_mut() {
-                let w = unsafe { &*v }.next;
-                let x = unsafe { &*w }.x.as_ref();
-                if w == self.dummy {
-                    debug_assert!(x.is_none());
-                } else {
-                    debug_assert!(x.is_some());
+        let u = unsafe { &*self.u };
+        self.u = u.next;
+        let x = u.x.as_ref();
+        debug_assert!(x.is_some());
+        x
+    }
+}
+
+impl<'a, T, I> IntoIterator for &'a BinaryTrie<T, I>
+where
+    I: PrefixIndex<T>,
+{
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct IntoIter<T> {
+    u: *mut Node<T>,
+    dummy: *mut Node<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.u == self.dummy {
+            return None;
+        }
+        let u = unsafe { Box::from_raw(self.u) };
+        self.u = u.next;
+        debug_assert!(u.x.is_some());
+        u.x
+    }
+}
+
+impl<T> Drop for IntoIter<T> {
+    fn drop(&mut self) {
+        for _ in &mut *self {}
+        unsafe { ptr::drop_in_place(self.dummy) };
+        unsafe { alloc::dealloc(self.dummy as *mut u8, alloc::Layout::new::<Node<T>>()) };
+    }
+}
+
+impl<T, I> IntoIterator for BinaryTrie<T, I> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // 葉以外のノードは先に解放して、葉は連結リストを辿りながら解放する
+        let mut stack = vec![self.root];
+        while let Some(u) = stack.pop() {
+            for &v in unsafe { &*u }.child.iter() {
+                if !v.is_null() && unsafe { &*v }.x.is_none() {
+                    stack.push(v);
                 }
-                x
-            } else {
-                unreachable!()
             }
+            unsafe { ptr::drop_in_place(u) };
+            unsafe { alloc::dealloc(u as *mut u8, alloc::Layout::new::<Node<T>>()) };
+        }
+        IntoIter {
+            u: unsafe { &*self.dummy }.next,
+            dummy: self.dummy,
+        }
+    }
+}
+
+// キーの整数値だけを使う
+impl<K, V> IntValue for Pair<K, V>
+where
+    K: IntValue,
+{
+    const BITS: u32 = K::BITS;
+
+    fn int_value(&self) -> u64 {
+        self.key.int_value()
+    }
+}
+
+impl<K, V> BinaryTrie<Pair<K, V>>
+where
+    K: IntValue,
+{
+    // key をキーに持つ葉を返す
+    // なければ NULL を返す
+    // O(w) time
+    fn find_pair_node(&self, key: &K) -> *mut Node<Pair<K, V>> {
+        let ix = key.int_value();
+        let u = self.find_node(ix);
+        match unsafe { &*u }.x.as_ref() {
+            Some(p) if p.int_value() == ix => u,
+            _ => ptr::null_mut(),
         }
     }
 }
 
+// BinaryTrie の要素を (キー, 値) にしたもの
+pub type BinaryTrieMap<K, V> = PairMap<K, V, BinaryTrie<Pair<K, V>>>;
+
+impl<K, V> PairSet<K, V> for BinaryTrie<Pair<K, V>>
+where
+    K: IntValue,
+{
+    // O(w) time
+    fn get_pair(&self, key: &K) -> Option<&Pair<K, V>> {
+        unsafe { self.find_pair_node(key).as_ref() }.and_then(|u| u.x.as_ref())
+    }
+
+    // O(w) time
+    fn get_pair_mut(&mut self, key: &K) -> Option<&mut Pair<K, V>> {
+        unsafe { self.find_pair_node(key).as_mut() }.and_then(|u| u.x.as_mut())
+    }
+
+    // O(w) time
+    fn find_pair(&self, key: &K) -> Option<&Pair<K, V>> {
+        let u = self.find_node(key.int_value());
+        // u = dummy なら None
+        unsafe { &*u }.x.as_ref()
+    }
+
+    // O(w) time
+    fn add_pair(&mut self, p: Pair<K, V>) -> &mut Pair<K, V> {
+        let u = self.add_node(p);
+        unsafe { (*u).x.as_mut() }.unwrap()
+    }
+
+    // O(w) time
+    fn remove_pair(&mut self, key: &K) -> Option<Pair<K, V>> {
+        self.remove_ix(key.int_value())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::BinaryTrie;
-    use interface::SSet;
+    use super::{BinaryTrie, BinaryTrieMap};
+    use interface::{testing, SSet};
     use rand::{rngs::SmallRng, Rng, SeedableRng};
     use std::collections::BTreeSet;
 
@@ -281,6 +641,49 @@ mod tests {
         assert!(!removed);
     }
 
+    #[test]
+    fn test_size() {
+        let mut binary_trie = BinaryTrie::<u8>::new();
+        binary_trie.add(1);
+        binary_trie.add(2);
+        binary_trie.add(2);
+        assert_eq!(binary_trie.size(), 2);
+        binary_trie.remove(&1);
+        binary_trie.remove(&1);
+        assert_eq!(binary_trie.size(), 1);
+    }
+
+    #[test]
+    fn test_remove_all_then_add() {
+        let mut binary_trie = BinaryTrie::<u8>::new();
+        binary_trie.add(1);
+        binary_trie.add(2);
+        binary_trie.remove(&1);
+        binary_trie.remove(&2);
+        assert_eq!(binary_trie.size(), 0);
+        assert_eq!(binary_trie.find(&0), None);
+        assert_eq!(binary_trie.iter().next(), None);
+        binary_trie.add(3);
+        binary_trie.add(0);
+        assert_eq!(binary_trie.find(&0), Some(&0));
+        assert_eq!(binary_trie.find(&1), Some(&3));
+        assert_eq!(binary_trie.find(&4), None);
+        assert!(binary_trie.iter().eq([0, 3].iter()));
+    }
+
+    #[test]
+    fn test_find_after_remove() {
+        let mut trie = BinaryTrie::new();
+        trie.add(12_u32);
+        trie.add(14);
+        trie.remove(&12);
+        trie.add(11);
+        // 12 を消したあとに jump が 12 の葉を指したままにならない
+        assert_eq!(trie.find(&15), None);
+        assert_eq!(trie.find(&13), Some(&14));
+        assert_eq!(trie.find(&0), Some(&11));
+    }
+
     #[test]
     fn test_random() {
         let mut rng = SmallRng::seed_from_u64(0);
@@ -300,4 +703,25 @@ mod tests {
             assert_eq!(binary_trie.remove(&x), btree_set.remove(&x));
         }
     }
+
+    #[test]
+    fn test_iter() {
+        testing::test_iter(BinaryTrie::new());
+    }
+
+    #[test]
+    fn test_range() {
+        testing::test_range(BinaryTrie::new());
+    }
+
+    #[test]
+    fn test_navigate() {
+        testing::test_navigate(BinaryTrie::new());
+    }
+
+    #[test]
+    fn test_map() {
+        testing::test_map_random(BinaryTrieMap::new());
+        testing::test_map_entry(BinaryTrieMap::new());
+    }
 }
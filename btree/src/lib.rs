@@ -0,0 +1,820 @@
+use std::{
+    cell::RefCell,
+    collections::BTreeSet,
+    fs::{File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use interface::SSet;
+
+// 固定長のバイト列との相互変換
+pub trait FixedSize: Sized {
+    const SIZE: usize;
+    fn write_bytes(&self, buf: &mut [u8]);
+    fn read_bytes(buf: &[u8]) -> Self;
+}
+
+macro_rules! impl_fixed_size {
+     ($($t:ty),+) => {
+         $(
+            impl FixedSize for $t {
+                const SIZE: usize = std::mem::size_of::<$t>();
+                fn write_bytes(&self, buf: &mut [u8]) {
+                    buf[..Self::SIZE].copy_from_slice(&self.to_le_bytes());
+                }
+                fn read_bytes(buf: &[u8]) -> Self {
+                    <$t>::from_le_bytes(buf[..Self::SIZE].try_into().unwrap())
+                }
+            }
+         )+
+     };
+}
+
+impl_fixed_size!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+// 固定長のブロックを番号で読み書きする
+pub trait BlockStore {
+    fn block_size(&self) -> usize;
+    // buf の長さが block_size でないときや、番号のブロックがないときは InvalidInput を返す
+    fn read_block(&mut self, i: usize, buf: &mut [u8]) -> io::Result<()>;
+    fn write_block(&mut self, i: usize, buf: &[u8]) -> io::Result<()>;
+    // 空いているブロックに buf を書いて、その番号を返す
+    fn place_block(&mut self, buf: &[u8]) -> io::Result<usize>;
+    fn free_block(&mut self, i: usize) -> io::Result<()>;
+}
+
+pub struct MemoryBlockStore {
+    block_size: usize,
+    blocks: Vec<Box<[u8]>>,
+    free: Vec<usize>,
+}
+
+impl MemoryBlockStore {
+    pub fn new(block_size: usize) -> Self {
+        Self {
+            block_size,
+            blocks: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+}
+
+impl MemoryBlockStore {
+    fn block_mut(&mut self, i: usize) -> io::Result<&mut [u8]> {
+        match self.blocks.get_mut(i) {
+            Some(block) => Ok(block),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "block index out of range",
+            )),
+        }
+    }
+}
+
+// buf の長さが block_size でなければ InvalidInput を返す
+fn check_len(block_size: usize, buf: &[u8]) -> io::Result<()> {
+    if buf.len() != block_size {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "buffer length must be block_size",
+        ));
+    }
+    Ok(())
+}
+
+impl BlockStore for MemoryBlockStore {
+    fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    fn read_block(&mut self, i: usize, buf: &mut [u8]) -> io::Result<()> {
+        check_len(self.block_size, buf)?;
+        buf.copy_from_slice(self.block_mut(i)?);
+        Ok(())
+    }
+
+    fn write_block(&mut self, i: usize, buf: &[u8]) -> io::Result<()> {
+        check_len(self.block_size, buf)?;
+        self.block_mut(i)?.copy_from_slice(buf);
+        Ok(())
+    }
+
+    fn place_block(&mut self, buf: &[u8]) -> io::Result<usize> {
+        check_len(self.block_size, buf)?;
+        match self.free.pop() {
+            Some(i) => {
+                self.block_mut(i)?.copy_from_slice(buf);
+                Ok(i)
+            }
+            None => {
+                self.blocks.push(buf.into());
+                Ok(self.blocks.len() - 1)
+            }
+        }
+    }
+
+    fn free_block(&mut self, i: usize) -> io::Result<()> {
+        self.block_mut(i)?;
+        self.free.push(i);
+        Ok(())
+    }
+}
+
+const NONE: u64 = u64::MAX;
+
+// ファイルの先頭に (block_size, ブロック数, 空きブロックのリストの先頭) を置き、その後ろにブロックを並べる
+// 空きブロックは先頭 8 バイトに次の空きブロックの番号を書いてつなぐ
+pub struct FileBlockStore {
+    file: File,
+    block_size: usize,
+    num_blocks: u64,
+    free_head: u64,
+}
+
+impl FileBlockStore {
+    const HEADER_SIZE: u64 = 24;
+
+    // path のファイルを空にして使う
+    pub fn create<P: AsRef<Path>>(path: P, block_size: usize) -> io::Result<Self> {
+        assert!(block_size >= 8, "block_size must be at least 8");
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        let mut store = Self {
+            file,
+            block_size,
+            num_blocks: 0,
+            free_head: NONE,
+        };
+        store.write_header()?;
+        Ok(store)
+    }
+
+    // create で作ったファイルを開く
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+        let mut header = [0; Self::HEADER_SIZE as usize];
+        file.read_exact(&mut header)?;
+        let block_size = u64::read_bytes(&header[0..8]) as usize;
+        if block_size < 8 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid block size",
+            ));
+        }
+        Ok(Self {
+            file,
+            block_size,
+            num_blocks: u64::read_bytes(&header[8..16]),
+            free_head: u64::read_bytes(&header[16..24]),
+        })
+    }
+
+    fn write_header(&mut self) -> io::Result<()> {
+        let mut header = [0; Self::HEADER_SIZE as usize];
+        (self.block_size as u64).write_bytes(&mut header[0..8]);
+        self.num_blocks.write_bytes(&mut header[8..16]);
+        self.free_head.write_bytes(&mut header[16..24]);
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.write_all(&header)
+    }
+
+    fn seek_block(&mut self, i: u64) -> io::Result<()> {
+        if i >= self.num_blocks {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "block index out of range",
+            ));
+        }
+        let offset = Self::HEADER_SIZE + i * self.block_size as u64;
+        self.file.seek(SeekFrom::Start(offset)).map(|_| ())
+    }
+}
+
+impl BlockStore for FileBlockStore {
+    fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    fn read_block(&mut self, i: usize, buf: &mut [u8]) -> io::Result<()> {
+        check_len(self.block_size, buf)?;
+        self.seek_block(i as u64)?;
+        self.file.read_exact(buf)
+    }
+
+    fn write_block(&mut self, i: usize, buf: &[u8]) -> io::Result<()> {
+        check_len(self.block_size, buf)?;
+        self.seek_block(i as u64)?;
+        self.file.write_all(buf)
+    }
+
+    fn place_block(&mut self, buf: &[u8]) -> io::Result<usize> {
+        check_len(self.block_size, buf)?;
+        let i = if self.free_head == NONE {
+            self.num_blocks += 1;
+            self.num_blocks - 1
+        } else {
+            let i = self.free_head;
+            let mut next = [0; 8];
+            self.seek_block(i)?;
+            self.file.read_exact(&mut next)?;
+            self.free_head = u64::read_bytes(&next);
+            i
+        };
+        self.write_header()?;
+        self.write_block(i as usize, buf)?;
+        Ok(i as usize)
+    }
+
+    fn free_block(&mut self, i: usize) -> io::Result<()> {
+        let mut next = [0; 8];
+        self.free_head.write_bytes(&mut next);
+        self.seek_block(i as u64)?;
+        self.file.write_all(&next)?;
+        self.free_head = i as u64;
+        self.write_header()
+    }
+}
+
+// keys は昇順
+// 葉なら children は空で、そうでなければ children.len() = keys.len() + 1
+struct Node<T> {
+    keys: Vec<T>,
+    children: Vec<usize>,
+}
+
+impl<T> Node<T> {
+    fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+enum Added<T> {
+    // すでにあった
+    Duplicate,
+    Done,
+    // 分割して (区切りの要素, 右側のノード) ができた
+    Split(T, usize),
+}
+
+// 根以外のノードは B - 1 個以上 2B - 1 個以下の要素を持つ
+// ブロック 0 には (根のブロック, 要素数) を置く
+// ブロックは (要素数, 子の数, 子 2B + 1 個, 要素 2B 個) の形で、B はブロックに収まる最大の値にする
+pub struct BTree<T, S> {
+    b: usize,
+    n: usize,
+    root: usize,
+    store: RefCell<S>,
+    // SSet::find で返した要素を次に self を変更するまで置いておく
+    // 返した参照は &self が生きている間は有効でなければならないので、途中で捨てずに同じ値をひとつずつ持つ
+    // 要素数より多くはならない
+    found: RefCell<BTreeSet<Box<T>>>,
+}
+
+impl<T, S> BTree<T, S>
+where
+    T: FixedSize + Ord,
+    S: BlockStore,
+{
+    const META: usize = 0;
+
+    // 空の store に空の BTree を作る
+    pub fn new(store: S) -> io::Result<Self> {
+        let b = Self::b_for(store.block_size())?;
+        let mut tree = Self {
+            b,
+            n: 0,
+            root: 0,
+            store: RefCell::new(store),
+            found: RefCell::new(BTreeSet::new()),
+        };
+        let buf = vec![0; tree.block_size()];
+        let meta = tree.store.get_mut().place_block(&buf)?;
+        if meta != Self::META {
+            tree.store.get_mut().free_block(meta)?;
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "store must be empty",
+            ));
+        }
+        tree.root = tree.place_node(&Node {
+            keys: Vec::new(),
+            children: Vec::new(),
+        })?;
+        tree.write_meta()?;
+        Ok(tree)
+    }
+
+    // new で作った BTree が入っている store から読み込む
+    pub fn open(store: S) -> io::Result<Self> {
+        let b = Self::b_for(store.block_size())?;
+        let mut tree = Self {
+            b,
+            n: 0,
+            root: 0,
+            store: RefCell::new(store),
+            found: RefCell::new(BTreeSet::new()),
+        };
+        let mut buf = vec![0; tree.block_size()];
+        tree.store.get_mut().read_block(Self::META, &mut buf)?;
+        tree.root = u64::read_bytes(&buf[0..8]) as usize;
+        tree.n = u64::read_bytes(&buf[8..16]) as usize;
+        Ok(tree)
+    }
+
+    pub fn into_store(self) -> S {
+        self.store.into_inner()
+    }
+
+    fn b_for(block_size: usize) -> io::Result<usize> {
+        // 16 + 8 (2B + 1) + T::SIZE 2B <= block_size
+        let b = block_size.saturating_sub(24) / (2 * (8 + T::SIZE));
+        if b < 2 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "block size is too small",
+            ));
+        }
+        Ok(b)
+    }
+
+    fn block_size(&self) -> usize {
+        self.store.borrow().block_size()
+    }
+
+    fn write_meta(&mut self) -> io::Result<()> {
+        let mut buf = vec![0; self.block_size()];
+        (self.root as u64).write_bytes(&mut buf[0..8]);
+        (self.n as u64).write_bytes(&mut buf[8..16]);
+        self.store.get_mut().write_block(Self::META, &buf)
+    }
+
+    fn encode(&self, u: &Node<T>) -> Vec<u8> {
+        let mut buf = vec![0; self.block_size()];
+        (u.keys.len() as u64).write_bytes(&mut buf[0..8]);
+        (u.children.len() as u64).write_bytes(&mut buf[8..16]);
+        let mut p = 16;
+        for &c in &u.children {
+            (c as u64).write_bytes(&mut buf[p..]);
+            p += 8;
+        }
+        let mut p = 16 + 8 * (2 * self.b + 1);
+        for x in &u.keys {
+            x.write_bytes(&mut buf[p..]);
+            p += T::SIZE;
+        }
+        buf
+    }
+
+    // O(B) time
+    fn read_node(&self, i: usize) -> io::Result<Node<T>> {
+        let mut buf = vec![0; self.block_size()];
+        self.store.borrow_mut().read_block(i, &mut buf)?;
+        let num_keys = u64::read_bytes(&buf[0..8]) as usize;
+        let num_children = u64::read_bytes(&buf[8..16]) as usize;
+        if num_keys > 2 * self.b || num_children > 2 * self.b + 1 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "broken node"));
+        }
+        let children = (0..num_children)
+            .map(|j| u64::read_bytes(&buf[16 + 8 * j..]) as usize)
+            .collect();
+        let p = 16 + 8 * (2 * self.b + 1);
+        let keys = (0..num_keys)
+            .map(|j| T::read_bytes(&buf[p + T::SIZE * j..]))
+            .collect();
+        Ok(Node { keys, children })
+    }
+
+    fn write_node(&mut self, i: usize, u: &Node<T>) -> io::Result<()> {
+        let buf = self.encode(u);
+        self.store.get_mut().write_block(i, &buf)
+    }
+
+    fn place_node(&mut self, u: &Node<T>) -> io::Result<usize> {
+        let buf = self.encode(u);
+        self.store.get_mut().place_block(&buf)
+    }
+
+    // x 以上の最小の要素を返す
+    // SSet::find と違って読んだ要素をそのまま返すので、self に要素を溜めない
+    // O(B log_B(n)) time, O(log_B(n)) 回のブロックの読み込み
+    pub fn try_find(&self, x: &T) -> io::Result<Option<T>> {
+        let mut z = None;
+        let mut ui = self.root;
+        loop {
+            let mut u = self.read_node(ui)?;
+            let i = match u.keys.binary_search(x) {
+                Ok(i) => return Ok(Some(u.keys.swap_remove(i))),
+                Err(i) => i,
+            };
+            if i < u.keys.len() {
+                z = Some(u.keys.swap_remove(i));
+            }
+            if u.is_leaf() {
+                return Ok(z);
+            }
+            ui = u.children[i];
+        }
+    }
+
+    // ui を根とする部分木に x を追加する
+    // ノードが 2B 個の要素を持ったら分割する
+    fn add_recursive(&mut self, x: T, ui: usize) -> io::Result<Added<T>> {
+        let mut u = self.read_node(ui)?;
+        let i = match u.keys.binary_search(&x) {
+            Ok(_) => return Ok(Added::Duplicate),
+            Err(i) => i,
+        };
+        if u.is_leaf() {
+            u.keys.insert(i, x);
+        } else {
+            match self.add_recursive(x, u.children[i])? {
+                Added::Split(y, wi) => {
+                    u.keys.insert(i, y);
+                    u.children.insert(i + 1, wi);
+                }
+                added => return Ok(added),
+            }
+        }
+        if u.keys.len() < 2 * self.b {
+            self.write_node(ui, &u)?;
+            return Ok(Added::Done);
+        }
+        // 左に B 個、右に B - 1 個
+        let w = Node {
+            keys: u.keys.split_off(self.b + 1),
+            children: if u.is_leaf() {
+                Vec::new()
+            } else {
+                u.children.split_off(self.b + 1)
+            },
+        };
+        let y = u.keys.pop().unwrap();
+        let wi = self.place_node(&w)?;
+        self.write_node(ui, &u)?;
+        Ok(Added::Split(y, wi))
+    }
+
+    // ui を根とする部分木から x を取り除く
+    fn remove_recursive(&mut self, x: &T, ui: usize) -> io::Result<bool> {
+        let mut u = self.read_node(ui)?;
+        match u.keys.binary_search(x) {
+            Ok(i) if u.is_leaf() => {
+                u.keys.remove(i);
+            }
+            Ok(i) => {
+                // 右の部分木の最小の要素と置き換える
+                u.keys[i] = self.remove_smallest(u.children[i + 1])?;
+                self.check_underflow(&mut u, i + 1)?;
+            }
+            Err(i) => {
+                if u.is_leaf() || !self.remove_recursive(x, u.children[i])? {
+                    return Ok(false);
+                }
+                self.check_underflow(&mut u, i)?;
+            }
+        }
+        self.write_node(ui, &u)?;
+        Ok(true)
+    }
+
+    // ui を根とする部分木から最小の要素を取り除いて返す
+    fn remove_smallest(&mut self, ui: usize) -> io::Result<T> {
+        let mut u = self.read_node(ui)?;
+        let y = if u.is_leaf() {
+            u.keys.remove(0)
+        } else {
+            let y = self.remove_smallest(u.children[0])?;
+            self.check_underflow(&mut u, 0)?;
+            y
+        };
+        self.write_node(ui, &u)?;
+        Ok(y)
+    }
+
+    // u の i 番目の子の要素が B - 2 個になっていたら、隣の子と併合するか要素を借りる
+    // O(B) time
+    fn check_underflow(&mut self, u: &mut Node<T>, i: usize) -> io::Result<()> {
+        let c = self.read_node(u.children[i])?;
+        if c.keys.len() + 1 >= self.b {
+            return Ok(());
+        }
+        // 隣の子と左右の組にする
+        let j = if i > 0 { i - 1 } else { i };
+        let (mut left, mut right) = if j == i {
+            let right = self.read_node(u.children[j + 1])?;
+            (c, right)
+        } else {
+            let left = self.read_node(u.children[j])?;
+            (left, c)
+        };
+        let li = u.children[j];
+        let ri = u.children[j + 1];
+        let y = u.keys.remove(j);
+        left.keys.push(y);
+        left.keys.append(&mut right.keys);
+        left.children.append(&mut right.children);
+        if left.keys.len() < 2 * self.b {
+            // 併合する
+            u.children.remove(j + 1);
+            self.write_node(li, &left)?;
+            self.store.get_mut().free_block(ri)?;
+        } else {
+            // 半分ずつに分け直す
+            let k = left.keys.len() / 2;
+            right.keys = left.keys.split_off(k + 1);
+            if !left.is_leaf() {
+                right.children = left.children.split_off(k + 1);
+            }
+            u.keys.insert(j, left.keys.pop().unwrap());
+            self.write_node(li, &left)?;
+            self.write_node(ri, &right)?;
+        }
+        Ok(())
+    }
+
+    // SSet::find で溜めた要素を捨てる
+    // 溜めた要素への参照は &mut self を取るので残っていない
+    pub fn clear_find_cache(&mut self) {
+        self.found.get_mut().clear();
+    }
+
+    // SSet::add と同じで、ブロックの読み書きに失敗したら io::Error を返す
+    // O(B log_B(n)) time, O(log_B(n)) 回のブロックの読み書き
+    pub fn try_add(&mut self, x: T) -> io::Result<bool> {
+        self.clear_find_cache();
+        match self.add_recursive(x, self.root)? {
+            Added::Duplicate => return Ok(false),
+            Added::Done => {}
+            Added::Split(y, wi) => {
+                let root = Node {
+                    keys: vec![y],
+                    children: vec![self.root, wi],
+                };
+                self.root = self.place_node(&root)?;
+            }
+        }
+        self.n += 1;
+        self.write_meta()?;
+        Ok(true)
+    }
+
+    // SSet::remove と同じで、ブロックの読み書きに失敗したら io::Error を返す
+    // O(B log_B(n)) time, O(log_B(n)) 回のブロックの読み書き
+    pub fn try_remove(&mut self, x: &T) -> io::Result<bool> {
+        self.clear_find_cache();
+        if !self.remove_recursive(x, self.root)? {
+            return Ok(false);
+        }
+        let root = self.read_node(self.root)?;
+        if root.keys.is_empty() && !root.is_leaf() {
+            // 根の要素がなくなったら高さを 1 減らす
+            self.store.get_mut().free_block(self.root)?;
+            self.root = root.children[0];
+        }
+        self.n -= 1;
+        self.write_meta()?;
+        Ok(true)
+    }
+}
+
+// SSet のメソッドは io::Error を返せないので panic する
+// io::Error を扱うときは try_add, try_remove, try_find を使う
+// SSet::find は返した要素を次に self を変更するまでメモリに置いておくので、
+// 変更せずに find を続けると、find で返した異なる要素の分だけメモリを使う
+// メモリに収まらない集合を読むときは try_find を使うか、clear_find_cache で捨てる
+impl<T, S> SSet<T> for BTree<T, S>
+where
+    T: FixedSize + Ord,
+    S: BlockStore,
+{
+    // O(1) time
+    fn size(&self) -> usize {
+        self.n
+    }
+
+    // O(B log_B(n)) time, O(log_B(n)) 回のブロックの読み書き
+    fn add(&mut self, x: T) -> bool {
+        self.try_add(x).expect("failed to add")
+    }
+
+    // O(B log_B(n)) time, O(log_B(n)) 回のブロックの読み書き
+    fn remove(&mut self, x: &T) -> bool {
+        self.try_remove(x).expect("failed to remove")
+    }
+
+    // O(B log_B(n)) time, O(log_B(n)) 回のブロックの読み込み
+    fn find(&self, x: &T) -> Option<&T> {
+        let y = self.try_find(x).expect("failed to find")?;
+        let mut found = self.found.borrow_mut();
+        let p: *const T = match found.get(&y) {
+            Some(z) => &**z,
+            None => {
+                let z = Box::new(y);
+                let p: *const T = &*z;
+                found.insert(z);
+                p
+            }
+        };
+        // Box の中身は self を変更するまで動かさない
+        Some(unsafe { &*p })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BTree, BlockStore, FileBlockStore, FixedSize, MemoryBlockStore};
+    use interface::SSet;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+    use std::collections::BTreeSet;
+    use std::io;
+
+    // 要素の個数, 順序, 葉の深さが揃っているかを調べて、要素を昇順に返す
+    fn validate<T, S>(tree: &BTree<T, S>) -> Vec<T>
+    where
+        T: FixedSize + Ord,
+        S: BlockStore,
+    {
+        fn dfs<T, S>(
+            tree: &BTree<T, S>,
+            ui: usize,
+            depth: usize,
+            leaf_depth: &mut Option<usize>,
+            out: &mut Vec<T>,
+        ) where
+            T: FixedSize + Ord,
+            S: BlockStore,
+        {
+            let u = tree.read_node(ui).unwrap();
+            if ui != tree.root {
+                assert!(u.keys.len() + 1 >= tree.b);
+            }
+            assert!(u.keys.len() < 2 * tree.b);
+            if u.is_leaf() {
+                assert_eq!(*leaf_depth.get_or_insert(depth), depth);
+                out.extend(u.keys);
+                return;
+            }
+            assert_eq!(u.children.len(), u.keys.len() + 1);
+            let mut keys = u.keys.into_iter();
+            for (j, &c) in u.children.iter().enumerate() {
+                dfs(tree, c, depth + 1, leaf_depth, out);
+                if j < u.children.len() - 1 {
+                    out.push(keys.next().unwrap());
+                }
+            }
+        }
+        let mut out = Vec::new();
+        dfs(tree, tree.root, 0, &mut None, &mut out);
+        assert!(out.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(out.len(), tree.size());
+        out
+    }
+
+    #[test]
+    fn test_find() {
+        let mut tree = BTree::new(MemoryBlockStore::new(4096)).unwrap();
+        assert_eq!(tree.find(&0_u32), None);
+        for x in (0..1000).map(|x| x * 10) {
+            assert!(tree.add(x));
+        }
+        assert!(!tree.add(0));
+        let a = tree.find(&0);
+        let b = tree.find(&1);
+        assert_eq!(a, Some(&0));
+        assert_eq!(b, Some(&10));
+        assert_eq!(tree.find(&9990), Some(&9990));
+        assert_eq!(tree.find(&9991), None);
+        // 同じ値を何度 find しても溜める要素は増えない
+        for _ in 0..100 {
+            assert_eq!(tree.find(&5), Some(&10));
+        }
+        assert_eq!(tree.found.borrow().len(), 3);
+        // try_find は要素を溜めない
+        assert_eq!(tree.try_find(&11).unwrap(), Some(20));
+        assert_eq!(tree.found.borrow().len(), 3);
+        assert!(tree.try_add(1).unwrap());
+        assert!(tree.found.borrow().is_empty());
+        assert!(tree.try_remove(&1).unwrap());
+        assert!(!tree.try_remove(&1).unwrap());
+        assert_eq!(tree.find(&5), Some(&10));
+        tree.clear_find_cache();
+        assert!(tree.found.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_new_non_empty() {
+        let mut tree = BTree::new(MemoryBlockStore::new(4096)).unwrap();
+        tree.add(0_u32);
+        let store = tree.into_store();
+        let err = BTree::<u32, _>::new(store).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        // B = 2, 3, 5
+        for &block_size in &[88, 120, 184] {
+            let mut tree = BTree::new(MemoryBlockStore::new(block_size)).unwrap();
+            assert_eq!(block_size, 24 + 2 * tree.b * (8 + 8));
+            let mut btree_set = BTreeSet::new();
+            for _ in 0..3000 {
+                let x = rng.gen_range(0..200_u64);
+                if rng.gen_bool(0.5) {
+                    assert_eq!(tree.add(x), btree_set.insert(x));
+                } else {
+                    assert_eq!(tree.remove(&x), btree_set.remove(&x));
+                }
+                let x = rng.gen_range(0..210);
+                assert_eq!(tree.find(&x), btree_set.range(x..).next());
+                let v = validate(&tree);
+                assert!(v.iter().eq(btree_set.iter()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_free_block() {
+        let mut tree = BTree::new(MemoryBlockStore::new(88)).unwrap();
+        for x in 0..1000_u32 {
+            tree.add(x);
+        }
+        for x in 0..1000 {
+            tree.remove(&x);
+        }
+        let store = tree.into_store();
+        // メタデータと根以外は解放されている
+        assert_eq!(store.blocks.len() - store.free.len(), 2);
+    }
+
+    // 範囲外の番号や長さの違う buf は panic せずに InvalidInput を返す
+    fn test_invalid_input<S: BlockStore>(mut store: S) {
+        let b = store.block_size();
+        let i = store.place_block(&vec![1; b]).unwrap();
+        let mut buf = vec![0; b];
+        store.read_block(i, &mut buf).unwrap();
+        assert_eq!(buf, vec![1; b]);
+        let kind = |r: io::Result<()>| r.unwrap_err().kind();
+        assert_eq!(
+            kind(store.read_block(i + 1, &mut buf)),
+            io::ErrorKind::InvalidInput
+        );
+        assert_eq!(
+            kind(store.write_block(i + 1, &buf)),
+            io::ErrorKind::InvalidInput
+        );
+        assert_eq!(
+            kind(store.read_block(i, &mut buf[1..])),
+            io::ErrorKind::InvalidInput
+        );
+        assert_eq!(
+            kind(store.write_block(i, &vec![0; b + 1])),
+            io::ErrorKind::InvalidInput
+        );
+        let e = store.place_block(&buf[1..]).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(kind(store.free_block(i + 1)), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_invalid_block() {
+        test_invalid_input(MemoryBlockStore::new(16));
+        let path = std::env::temp_dir().join(format!("btree-test-invalid-{}", std::process::id()));
+        test_invalid_input(FileBlockStore::create(&path, 16).unwrap());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_file() {
+        let path = std::env::temp_dir().join(format!("btree-test-{}", std::process::id()));
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut btree_set = BTreeSet::new();
+        {
+            let store = FileBlockStore::create(&path, 256).unwrap();
+            let mut tree = BTree::new(store).unwrap();
+            for _ in 0..2000 {
+                let x = rng.gen_range(0..500_i32);
+                assert_eq!(tree.add(x), btree_set.insert(x));
+            }
+        }
+        // 開き直しても要素が残っている
+        for _ in 0..3 {
+            let store = FileBlockStore::open(&path).unwrap();
+            assert_eq!(store.block_size(), 256);
+            let mut tree = BTree::open(store).unwrap();
+            assert!(validate(&tree).iter().eq(btree_set.iter()));
+            for _ in 0..500 {
+                let x = rng.gen_range(0..500);
+                assert_eq!(tree.remove(&x), btree_set.remove(&x));
+                let x = rng.gen_range(0..500);
+                assert_eq!(tree.add(x), btree_set.insert(x));
+            }
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,168 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use interface::USet;
+
+pub struct ChainedHashTable<T> {
+    t: Vec<Vec<T>>, // t.len() = 2^d
+    n: usize,
+    d: u32,
+    z: u64, // 奇数
+}
+
+impl<T> ChainedHashTable<T>
+where
+    T: Hash + Eq,
+{
+    // z はインスタンスごとに OS の乱数から決める
+    pub fn new() -> Self {
+        Self::with_z(SmallRng::from_entropy().gen())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_z(SmallRng::seed_from_u64(seed).gen())
+    }
+
+    fn with_z(z: u64) -> Self {
+        Self {
+            t: Self::alloc_table(1),
+            n: 0,
+            d: 1,
+            z: z | 1,
+        }
+    }
+
+    fn alloc_table(d: u32) -> Vec<Vec<T>> {
+        (0..(1 << d)).map(|_| Vec::new()).collect()
+    }
+
+    // 乗算ハッシュ法
+    // (z * hash_code(x) mod 2^w) div 2^(w-d)
+    fn hash(&self, x: &T) -> usize {
+        let mut hasher = DefaultHasher::new();
+        x.hash(&mut hasher);
+        (self.z.wrapping_mul(hasher.finish()) >> (u64::BITS - self.d)) as usize
+    }
+
+    // 2^d > n となる最小の d で作り直す
+    // O(n) time
+    fn resize(&mut self) {
+        let mut d = 1;
+        while (1 << d) <= self.n {
+            d += 1;
+        }
+        let old = std::mem::replace(&mut self.t, Self::alloc_table(d));
+        self.d = d;
+        for x in old.into_iter().flatten() {
+            let j = self.hash(&x);
+            self.t[j].push(x);
+        }
+    }
+}
+
+impl<T> Default for ChainedHashTable<T>
+where
+    T: Hash + Eq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> USet<T> for ChainedHashTable<T>
+where
+    T: Hash + Eq,
+{
+    fn size(&self) -> usize {
+        self.n
+    }
+
+    // amortized expected O(1) time
+    fn add(&mut self, x: T) -> bool {
+        if self.find(&x).is_some() {
+            return false;
+        }
+        if self.n + 1 > self.t.len() {
+            self.resize();
+        }
+        let j = self.hash(&x);
+        self.t[j].push(x);
+        self.n += 1;
+        true
+    }
+
+    // amortized expected O(1) time
+    fn remove(&mut self, x: &T) -> bool {
+        let j = self.hash(x);
+        match self.t[j].iter().position(|y| y.eq(x)) {
+            Some(i) => {
+                // 連結リストの中の順番は気にしないので末尾と入れ替えて消す
+                self.t[j].swap_remove(i);
+                self.n -= 1;
+                if self.n * 3 < self.t.len() {
+                    self.resize();
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    // expected O(1) time
+    fn find(&self, x: &T) -> Option<&T> {
+        let j = self.hash(x);
+        self.t[j].iter().find(|y| y.eq(&x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChainedHashTable;
+    use interface::USet;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_add_remove() {
+        let mut table = ChainedHashTable::new();
+        assert!(table.add("a"));
+        assert!(!table.add("a"));
+        assert!(table.add("b"));
+        assert_eq!(table.size(), 2);
+        assert_eq!(table.find(&"a"), Some(&"a"));
+        assert_eq!(table.find(&"c"), None);
+        assert!(table.remove(&"a"));
+        assert!(!table.remove(&"a"));
+        assert_eq!(table.find(&"a"), None);
+        assert_eq!(table.size(), 1);
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut table = ChainedHashTable::with_seed(0);
+        let mut hash_set = HashSet::new();
+        for _ in 0..1000 {
+            let x = rng.gen_range(0..200);
+            match rng.gen_range(0..3) {
+                0 => {
+                    assert_eq!(table.add(x), hash_set.insert(x));
+                }
+                1 => {
+                    assert_eq!(table.remove(&x), hash_set.remove(&x));
+                }
+                _ => {
+                    assert_eq!(table.find(&x), hash_set.get(&x));
+                }
+            }
+            assert_eq!(table.size(), hash_set.len());
+            // n <= t.len() <= 3n を保つ (n = 0 のときは t.len() = 2)
+            assert!(table.size() <= table.t.len());
+            assert!(table.t.len() <= (table.size() * 3).max(2));
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::{alloc, ptr};
 
-use interface::List;
+use interface::{Deque, List};
 
 #[derive(Debug)]
 struct Node<T> {
@@ -92,6 +92,12 @@ impl<T> DLList<T> {
     }
 }
 
+impl<T> Default for DLList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T> List<T> for DLList<T> {
     fn size(&self) -> usize {
         self.n
@@ -129,6 +135,44 @@ impl<T> List<T> for DLList<T> {
     }
 }
 
+impl<T> Deque<T> for DLList<T> {
+    // O(1) time
+    fn add_first(&mut self, x: T) {
+        let w = unsafe { &*self.dummy }.next;
+        self.add_before(w, x);
+    }
+
+    // O(1) time
+    fn add_last(&mut self, x: T) {
+        self.add_before(self.dummy, x);
+    }
+
+    // O(1) time
+    fn remove_first(&mut self) -> Option<T> {
+        // 空なら next = dummy
+        let w = unsafe { &*self.dummy }.next;
+        self.remove_node(w)
+    }
+
+    // O(1) time
+    fn remove_last(&mut self) -> Option<T> {
+        let w = unsafe { &*self.dummy }.prev;
+        self.remove_node(w)
+    }
+
+    // O(1) time
+    fn get_first(&self) -> Option<&T> {
+        let w = unsafe { &*self.dummy }.next;
+        unsafe { &*w }.x.as_ref()
+    }
+
+    // O(1) time
+    fn get_last(&self) -> Option<&T> {
+        let w = unsafe { &*self.dummy }.prev;
+        unsafe { &*w }.x.as_ref()
+    }
+}
+
 impl<T> Drop for DLList<T> {
     fn drop(&mut self) {
         while self.size() > 0 {
@@ -142,7 +186,7 @@ impl<T> Drop for DLList<T> {
 #[cfg(test)]
 mod tests {
     use super::DLList;
-    use interface::List;
+    use interface::{Deque, List};
 
     #[test]
     fn test_get_none() {
@@ -225,4 +269,23 @@ mod tests {
 
         assert_eq!(list.size(), 0);
     }
+
+    #[test]
+    fn test_deque() {
+        let mut deque = DLList::new();
+        assert_eq!(deque.get_first(), None);
+        assert_eq!(deque.remove_last(), None);
+        deque.add_last('b');
+        deque.add_first('a');
+        deque.add_last('c');
+        // a b c
+        assert_eq!(deque.get_first(), Some(&'a'));
+        assert_eq!(deque.get_last(), Some(&'c'));
+        assert_eq!(deque.get(1), Some(&'b'));
+        assert_eq!(deque.remove_first(), Some('a'));
+        assert_eq!(deque.remove_last(), Some('c'));
+        assert_eq!(deque.remove_last(), Some('b'));
+        assert_eq!(deque.remove_first(), None);
+        assert_eq!(deque.size(), 0);
+    }
 }
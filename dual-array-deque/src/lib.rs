@@ -0,0 +1,224 @@
+use array_stack::ArrayStack;
+use interface::{Deque, List, Stack};
+
+// front は先頭側の要素を逆順に、back は末尾側の要素をそのままの順で持つ
+// front: [x_{k-1}, ..., x_1, x_0]
+// back:  [x_k, x_{k+1}, ..., x_{n-1}]
+pub struct DualArrayDeque<T> {
+    front: ArrayStack<T>,
+    back: ArrayStack<T>,
+}
+
+impl<T> DualArrayDeque<T> {
+    pub fn new() -> Self {
+        Self {
+            front: ArrayStack::new(),
+            back: ArrayStack::new(),
+        }
+    }
+
+    // front と back の大きさが 3 倍以上違うときに半分ずつに分け直す
+    // O(n) time
+    fn balance(&mut self) {
+        let nf = self.front.size();
+        let nb = self.back.size();
+        if nf * 3 >= nb && nb * 3 >= nf {
+            return;
+        }
+        let n = nf + nb;
+        let mut xs = Vec::with_capacity(n);
+        while let Some(x) = self.front.pop() {
+            xs.push(x);
+        }
+        let mut ys = Vec::with_capacity(nb);
+        while let Some(x) = self.back.pop() {
+            ys.push(x);
+        }
+        xs.extend(ys.into_iter().rev());
+        // xs = [x_0, x_1, ..., x_{n-1}]
+        let back = xs.split_off(n / 2);
+        for x in xs.into_iter().rev() {
+            self.front.push(x);
+        }
+        for x in back {
+            self.back.push(x);
+        }
+    }
+}
+
+impl<T> Default for DualArrayDeque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> List<T> for DualArrayDeque<T> {
+    fn size(&self) -> usize {
+        self.front.size() + self.back.size()
+    }
+
+    // O(1) time
+    fn get(&self, i: usize) -> Option<&T> {
+        let nf = self.front.size();
+        if i < nf {
+            self.front.get(nf - i - 1)
+        } else {
+            self.back.get(i - nf)
+        }
+    }
+
+    // O(1) time
+    fn set(&self, i: usize, x: T) -> T {
+        assert!(i < self.size(), "expect `i` < DualArrayDeque::size()");
+        let nf = self.front.size();
+        if i < nf {
+            self.front.set(nf - i - 1, x)
+        } else {
+            self.back.set(i - nf, x)
+        }
+    }
+
+    // amortized O(1 + min(i, n-i)) time
+    fn add(&mut self, i: usize, x: T) {
+        assert!(i <= self.size(), "expect `i` <= DualArrayDeque::size()");
+        let nf = self.front.size();
+        if i < nf {
+            self.front.add(nf - i, x);
+        } else {
+            self.back.add(i - nf, x);
+        }
+        self.balance();
+    }
+
+    // amortized O(1 + min(i, n-i)) time
+    fn remove(&mut self, i: usize) -> T {
+        assert!(i < self.size(), "expect `i` < DualArrayDeque::size()");
+        let nf = self.front.size();
+        let x = if i < nf {
+            self.front.remove(nf - i - 1)
+        } else {
+            self.back.remove(i - nf)
+        };
+        self.balance();
+        x
+    }
+}
+
+impl<T> Deque<T> for DualArrayDeque<T> {
+    // amortized O(1) time
+    fn add_first(&mut self, x: T) {
+        self.add(0, x);
+    }
+
+    // amortized O(1) time
+    fn add_last(&mut self, x: T) {
+        self.add(self.size(), x);
+    }
+
+    // amortized O(1) time
+    fn remove_first(&mut self) -> Option<T> {
+        if self.size() == 0 {
+            None
+        } else {
+            Some(self.remove(0))
+        }
+    }
+
+    // amortized O(1) time
+    fn remove_last(&mut self) -> Option<T> {
+        if self.size() == 0 {
+            None
+        } else {
+            Some(self.remove(self.size() - 1))
+        }
+    }
+
+    // O(1) time
+    fn get_first(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    // O(1) time
+    fn get_last(&self) -> Option<&T> {
+        if self.size() == 0 {
+            None
+        } else {
+            self.get(self.size() - 1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DualArrayDeque;
+    use interface::{Deque, List};
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+    use std::collections::VecDeque;
+
+    #[test]
+    fn test_set_get() {
+        let mut list = DualArrayDeque::new();
+        list.add(0, 'b');
+        list.add(0, 'a');
+        list.add(2, 'c');
+        // a b c
+        assert_eq!(list.set(1, 'x'), 'b');
+        // a x c
+        assert_eq!(list.get(0), Some(&'a'));
+        assert_eq!(list.get(1), Some(&'x'));
+        assert_eq!(list.get(2), Some(&'c'));
+        assert_eq!(list.get(3), None);
+    }
+
+    #[test]
+    fn test_random_list() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut v = Vec::new();
+        let mut list = DualArrayDeque::new();
+        for _ in 0..1000 {
+            if v.is_empty() || rng.gen_bool(0.6) {
+                let i = rng.gen_range(0..=v.len());
+                let x = rng.gen::<u32>();
+                v.insert(i, x);
+                list.add(i, x);
+            } else {
+                let i = rng.gen_range(0..v.len());
+                assert_eq!(list.remove(i), v.remove(i));
+            }
+            assert_eq!(list.size(), v.len());
+            let (nf, nb) = (list.front.size(), list.back.size());
+            assert!(nf + nb < 2 || (nf * 3 >= nb && nb * 3 >= nf));
+        }
+        for i in 0..v.len() {
+            assert_eq!(list.get(i), v.get(i));
+        }
+    }
+
+    #[test]
+    fn test_random_deque() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut deque = DualArrayDeque::new();
+        let mut vec_deque = VecDeque::new();
+        for _ in 0..1000 {
+            let x = rng.gen::<u32>();
+            match rng.gen_range(0..4) {
+                0 => {
+                    deque.add_first(x);
+                    vec_deque.push_front(x);
+                }
+                1 => {
+                    deque.add_last(x);
+                    vec_deque.push_back(x);
+                }
+                2 => {
+                    assert_eq!(deque.remove_first(), vec_deque.pop_front());
+                }
+                _ => {
+                    assert_eq!(deque.remove_last(), vec_deque.pop_back());
+                }
+            }
+            assert_eq!(deque.get_first(), vec_deque.front());
+            assert_eq!(deque.get_last(), vec_deque.back());
+        }
+    }
+}
@@ -0,0 +1,275 @@
+use array_stack::ArrayStack;
+use interface::{Graph, List, Queue, Stack};
+use sllist::SLList;
+
+// a[i * n + j] は辺 (i, j) があるかどうか
+pub struct AdjacencyMatrix {
+    n: usize,
+    a: Vec<bool>,
+}
+
+impl AdjacencyMatrix {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            a: vec![false; n * n],
+        }
+    }
+
+    // j >= n でも i * n + j が範囲内になることがあるので確かめる
+    fn index(&self, i: usize, j: usize) -> usize {
+        assert!(i < self.n && j < self.n, "expect `i`, `j` < size()");
+        i * self.n + j
+    }
+}
+
+impl Graph for AdjacencyMatrix {
+    fn size(&self) -> usize {
+        self.n
+    }
+
+    // O(1) time
+    fn add_edge(&mut self, i: usize, j: usize) {
+        let k = self.index(i, j);
+        self.a[k] = true;
+    }
+
+    // O(1) time
+    fn remove_edge(&mut self, i: usize, j: usize) {
+        let k = self.index(i, j);
+        self.a[k] = false;
+    }
+
+    // O(1) time
+    fn has_edge(&self, i: usize, j: usize) -> bool {
+        self.a[self.index(i, j)]
+    }
+
+    // O(n) time
+    fn out_edges(&self, i: usize) -> Vec<usize> {
+        (0..self.n).filter(|&j| self.has_edge(i, j)).collect()
+    }
+
+    // O(n) time
+    fn in_edges(&self, i: usize) -> Vec<usize> {
+        (0..self.n).filter(|&j| self.has_edge(j, i)).collect()
+    }
+}
+
+// adj[i] は (i, j) が辺であるような j を持つ
+pub struct AdjacencyLists {
+    adj: Vec<ArrayStack<usize>>,
+}
+
+impl AdjacencyLists {
+    pub fn new(n: usize) -> Self {
+        Self {
+            adj: (0..n).map(|_| ArrayStack::new()).collect(),
+        }
+    }
+
+    // adj[i] の中で j がある位置
+    // 範囲外の j を入れると bfs, dfs で panic するので、ここで確かめる
+    // O(deg(i)) time
+    fn position(&self, i: usize, j: usize) -> Option<usize> {
+        let n = self.adj.len();
+        assert!(i < n && j < n, "expect `i`, `j` < size()");
+        let adj = &self.adj[i];
+        (0..adj.size()).find(|&k| adj.get(k) == Some(&j))
+    }
+}
+
+impl Graph for AdjacencyLists {
+    fn size(&self) -> usize {
+        self.adj.len()
+    }
+
+    // O(deg(i)) time
+    fn add_edge(&mut self, i: usize, j: usize) {
+        if self.position(i, j).is_none() {
+            let k = self.adj[i].size();
+            self.adj[i].add(k, j);
+        }
+    }
+
+    // O(deg(i)) time
+    fn remove_edge(&mut self, i: usize, j: usize) {
+        if let Some(k) = self.position(i, j) {
+            self.adj[i].remove(k);
+        }
+    }
+
+    // O(deg(i)) time
+    fn has_edge(&self, i: usize, j: usize) -> bool {
+        self.position(i, j).is_some()
+    }
+
+    // O(deg(i)) time
+    fn out_edges(&self, i: usize) -> Vec<usize> {
+        let adj = &self.adj[i];
+        (0..adj.size()).map(|k| *adj.get(k).unwrap()).collect()
+    }
+
+    // O(n + m) time
+    fn in_edges(&self, i: usize) -> Vec<usize> {
+        (0..self.size()).filter(|&j| self.has_edge(j, i)).collect()
+    }
+}
+
+// r から到達できる頂点を幅優先探索で訪れた順に返す
+// out_edges の計算量を除いて O(n + m) time
+pub fn bfs(g: &impl Graph, r: usize) -> Vec<usize> {
+    let mut seen = vec![false; g.size()];
+    let mut order = Vec::new();
+    let mut q = SLList::new();
+    seen[r] = true;
+    q.add(r);
+    while let Some(i) = q.remove() {
+        order.push(i);
+        for j in g.out_edges(i) {
+            if !seen[j] {
+                seen[j] = true;
+                q.add(j);
+            }
+        }
+    }
+    order
+}
+
+// r から到達できる頂点を深さ優先探索で訪れた順に返す
+// 再帰の代わりにスタックを使うので、訪れる順は再帰で書いたものと異なる
+// out_edges の計算量を除いて O(n + m) time
+pub fn dfs(g: &impl Graph, r: usize) -> Vec<usize> {
+    let mut seen = vec![false; g.size()];
+    let mut order = Vec::new();
+    let mut s = SLList::new();
+    s.push(r);
+    while let Some(i) = s.pop() {
+        if seen[i] {
+            continue;
+        }
+        seen[i] = true;
+        order.push(i);
+        for j in g.out_edges(i) {
+            if !seen[j] {
+                s.push(j);
+            }
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bfs, dfs, AdjacencyLists, AdjacencyMatrix};
+    use interface::Graph;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+    #[test]
+    fn test_random() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let n = 20;
+        let mut matrix = AdjacencyMatrix::new(n);
+        let mut lists = AdjacencyLists::new(n);
+        for _ in 0..2000 {
+            let i = rng.gen_range(0..n);
+            let j = rng.gen_range(0..n);
+            if rng.gen_bool(0.6) {
+                matrix.add_edge(i, j);
+                lists.add_edge(i, j);
+            } else {
+                matrix.remove_edge(i, j);
+                lists.remove_edge(i, j);
+            }
+            let i = rng.gen_range(0..n);
+            let j = rng.gen_range(0..n);
+            assert_eq!(matrix.has_edge(i, j), lists.has_edge(i, j));
+            let mut out_edges = lists.out_edges(i);
+            out_edges.sort_unstable();
+            assert_eq!(matrix.out_edges(i), out_edges);
+            assert_eq!(matrix.in_edges(i), lists.in_edges(i));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_matrix_out_of_range() {
+        // (0, 3) は a[3] = (1, 0) と同じ位置になる
+        let mut g = AdjacencyMatrix::new(3);
+        g.add_edge(0, 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_lists_out_of_range() {
+        let mut g = AdjacencyLists::new(3);
+        g.add_edge(0, 3);
+    }
+
+    #[test]
+    fn test_bfs() {
+        // 0 -> 1 -> 3 -> 4
+        // 0 -> 2 -> 3
+        // 5 -> 0
+        let mut g = AdjacencyLists::new(6);
+        for &(i, j) in &[(0, 1), (1, 3), (3, 4), (0, 2), (2, 3), (5, 0)] {
+            g.add_edge(i, j);
+        }
+        assert_eq!(bfs(&g, 0), vec![0, 1, 2, 3, 4]);
+        assert_eq!(bfs(&g, 5), vec![5, 0, 1, 2, 3, 4]);
+        assert_eq!(bfs(&g, 4), vec![4]);
+    }
+
+    #[test]
+    fn test_dfs() {
+        let mut g = AdjacencyMatrix::new(6);
+        for &(i, j) in &[(0, 1), (1, 3), (3, 4), (0, 2), (2, 3), (5, 0)] {
+            g.add_edge(i, j);
+        }
+        // 後に push した 2 を先に訪れる
+        assert_eq!(dfs(&g, 0), vec![0, 2, 3, 4, 1]);
+        assert_eq!(dfs(&g, 4), vec![4]);
+    }
+
+    #[test]
+    fn test_traversal_random() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let n = 30;
+        let mut g = AdjacencyLists::new(n);
+        for _ in 0..40 {
+            g.add_edge(rng.gen_range(0..n), rng.gen_range(0..n));
+        }
+        // 到達できる頂点を愚直に求める
+        let mut reachable = vec![false; n];
+        reachable[0] = true;
+        for _ in 0..n {
+            for i in 0..n {
+                for j in 0..n {
+                    if reachable[i] && g.has_edge(i, j) {
+                        reachable[j] = true;
+                    }
+                }
+            }
+        }
+        let expected: Vec<usize> = (0..n).filter(|&i| reachable[i]).collect();
+        for order in [bfs(&g, 0), dfs(&g, 0)] {
+            let mut sorted = order.clone();
+            sorted.sort_unstable();
+            assert_eq!(sorted, expected);
+            // 最初以外の頂点は、それより前に訪れた頂点から辺が出ている
+            for (k, &j) in order.iter().enumerate().skip(1) {
+                assert!(order[..k].iter().any(|&i| g.has_edge(i, j)));
+            }
+        }
+        // BFS は根からの距離の順に訪れる
+        let order = bfs(&g, 0);
+        let mut dist = vec![usize::MAX; n];
+        dist[0] = 0;
+        for &i in &order {
+            for j in g.out_edges(i) {
+                dist[j] = dist[j].min(dist[i] + 1);
+            }
+        }
+        assert!(order.windows(2).all(|w| dist[w[0]] <= dist[w[1]]));
+    }
+}
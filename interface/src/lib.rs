@@ -1,3 +1,5 @@
+use std::{cmp::Ordering, marker::PhantomData, mem, ops::RangeBounds, ptr};
+
 pub trait Queue<T> {
     fn add(&mut self, x: T);
     fn remove(&mut self) -> Option<T>;
@@ -8,6 +10,23 @@ pub trait Stack<T> {
     fn pop(&mut self) -> Option<T>;
 }
 
+pub trait Deque<T> {
+    fn add_first(&mut self, x: T);
+    fn add_last(&mut self, x: T);
+    fn remove_first(&mut self) -> Option<T>;
+    fn remove_last(&mut self) -> Option<T>;
+    fn get_first(&self) -> Option<&T>;
+    fn get_last(&self) -> Option<&T>;
+}
+
+pub trait PriorityQueue<T> {
+    fn size(&self) -> usize;
+    fn add(&mut self, x: T);
+    // 最小の要素を取り除いて返す
+    fn remove_min(&mut self) -> Option<T>;
+    fn peek_min(&self) -> Option<&T>;
+}
+
 pub trait List<T> {
     fn size(&self) -> usize;
     fn get(&self, i: usize) -> Option<&T>;
@@ -23,3 +42,487 @@ pub trait SSet<T> {
     // lower bound
     fn find(&self, x: &T) -> Option<&T>;
 }
+
+pub trait RangeSSet<'a, T>: SSet<T>
+where
+    T: 'a,
+{
+    type Range: Iterator<Item = &'a T>;
+    // range に含まれる要素を昇順に返す
+    fn range<R>(&'a self, range: R) -> Self::Range
+    where
+        R: RangeBounds<T>;
+}
+
+pub trait NavigableSSet<T>: SSet<T> {
+    // x より大きい最小の要素
+    fn find_gt(&self, x: &T) -> Option<&T>;
+    // x 以下の最大の要素
+    fn find_le(&self, x: &T) -> Option<&T>;
+    // x より小さい最大の要素
+    fn find_lt(&self, x: &T) -> Option<&T>;
+    fn first(&self) -> Option<&T>;
+    fn last(&self) -> Option<&T>;
+}
+
+pub trait SMap<K, V> {
+    fn size(&self) -> usize;
+    // key がすでにあれば値を置き換えて古い値を返す
+    fn insert(&mut self, key: K, value: V) -> Option<V>;
+    fn get(&self, key: &K) -> Option<&V>;
+    fn get_mut(&mut self, key: &K) -> Option<&mut V>;
+    fn remove(&mut self, key: &K) -> Option<V>;
+    // key 以上の最小のキーとその値
+    fn find(&self, key: &K) -> Option<(&K, &V)>;
+    fn entry(&mut self, key: K) -> Entry<'_, K, V>;
+}
+
+pub enum Entry<'a, K, V> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+impl<'a, K, V> Entry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(e) => e.key(),
+            Entry::Vacant(e) => e.key(),
+        }
+    }
+
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(default),
+        }
+    }
+
+    pub fn or_insert_with<F>(self, default: F) -> &'a mut V
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(default()),
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&mut V),
+    {
+        match self {
+            Entry::Occupied(mut e) => {
+                f(e.get_mut());
+                Entry::Occupied(e)
+            }
+            Entry::Vacant(e) => Entry::Vacant(e),
+        }
+    }
+}
+
+pub struct OccupiedEntry<'a, K, V> {
+    key: &'a K,
+    value: &'a mut V,
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+    pub fn new(key: &'a K, value: &'a mut V) -> Self {
+        Self { key, value }
+    }
+
+    pub fn key(&self) -> &K {
+        self.key
+    }
+
+    pub fn get(&self) -> &V {
+        self.value
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        self.value
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        self.value
+    }
+
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.value, value)
+    }
+}
+
+// 挿入の仕方は実装ごとに違うので、挿入する関数を持たせておく
+pub struct VacantEntry<'a, K, V> {
+    key: K,
+    insert: Box<dyn FnOnce(K, V) -> &'a mut V + 'a>,
+}
+
+impl<'a, K, V> VacantEntry<'a, K, V> {
+    pub fn new<F>(key: K, insert: F) -> Self
+    where
+        F: FnOnce(K, V) -> &'a mut V + 'a,
+    {
+        Self {
+            key,
+            insert: Box::new(insert),
+        }
+    }
+
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    pub fn insert(self, value: V) -> &'a mut V {
+        (self.insert)(self.key, value)
+    }
+}
+
+// キーと値の組で、キーだけで比べる
+pub struct Pair<K, V> {
+    pub key: K,
+    pub value: V,
+}
+
+impl<K, V> PartialEq for Pair<K, V>
+where
+    K: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<K, V> Eq for Pair<K, V> where K: Eq {}
+
+impl<K, V> PartialOrd for Pair<K, V>
+where
+    K: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.key.partial_cmp(&other.key)
+    }
+}
+
+impl<K, V> Ord for Pair<K, V>
+where
+    K: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+// Pair を要素に持つ SSet で、PairMap から使う
+pub trait PairSet<K, V>: SSet<Pair<K, V>> {
+    // key をキーに持つ組
+    fn get_pair(&self, key: &K) -> Option<&Pair<K, V>>;
+    fn get_pair_mut(&mut self, key: &K) -> Option<&mut Pair<K, V>>;
+    // key 以上の最小のキーを持つ組
+    fn find_pair(&self, key: &K) -> Option<&Pair<K, V>>;
+    // p のキーを持つ組はまだないとして、p を追加して返す
+    fn add_pair(&mut self, p: Pair<K, V>) -> &mut Pair<K, V>;
+    fn remove_pair(&mut self, key: &K) -> Option<Pair<K, V>>;
+}
+
+// PairSet の要素を (キー, 値) として使う SMap
+// どの操作も PairSet の操作 1 回分の時間で済む
+pub struct PairMap<K, V, S> {
+    set: S,
+    marker: PhantomData<Pair<K, V>>,
+}
+
+impl<K, V, S> PairMap<K, V, S> {
+    pub fn with_set(set: S) -> Self {
+        Self {
+            set,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<K, V, S> PairMap<K, V, S>
+where
+    S: Default,
+{
+    pub fn new() -> Self {
+        Self::with_set(S::default())
+    }
+}
+
+impl<K, V, S> Default for PairMap<K, V, S>
+where
+    S: Default,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, S> SMap<K, V> for PairMap<K, V, S>
+where
+    S: PairSet<K, V>,
+{
+    fn size(&self) -> usize {
+        self.set.size()
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(p) = self.set.get_pair_mut(&key) {
+            return Some(mem::replace(&mut p.value, value));
+        }
+        self.set.add_pair(Pair { key, value });
+        None
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        self.set.get_pair(key).map(|p| &p.value)
+    }
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.set.get_pair_mut(key).map(|p| &mut p.value)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        self.set.remove_pair(key).map(|p| p.value)
+    }
+
+    fn find(&self, key: &K) -> Option<(&K, &V)> {
+        self.set.find_pair(key).map(|p| (&p.key, &p.value))
+    }
+
+    fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        // 見つからなかったときに self.set を借用し直せるようにポインタにしておく
+        let p: *mut Pair<K, V> = match self.set.get_pair_mut(&key) {
+            Some(p) => p,
+            None => ptr::null_mut(),
+        };
+        if p.is_null() {
+            Entry::Vacant(VacantEntry::new(key, move |key, value| {
+                &mut self.set.add_pair(Pair { key, value }).value
+            }))
+        } else {
+            let p = unsafe { &mut *p };
+            Entry::Occupied(OccupiedEntry::new(&p.key, &mut p.value))
+        }
+    }
+}
+
+pub trait USet<T> {
+    fn size(&self) -> usize;
+    fn add(&mut self, x: T) -> bool;
+    fn remove(&mut self, x: &T) -> bool;
+    // x に等しい要素
+    fn find(&self, x: &T) -> Option<&T>;
+}
+
+// 頂点は 0, 1, ..., n - 1 の有向グラフ
+pub trait Graph {
+    // 頂点の数
+    fn size(&self) -> usize;
+    // すでにある辺を追加しても何も起きない
+    fn add_edge(&mut self, i: usize, j: usize);
+    fn remove_edge(&mut self, i: usize, j: usize);
+    fn has_edge(&self, i: usize, j: usize) -> bool;
+    // (i, j) が辺であるような j
+    fn out_edges(&self, i: usize) -> Vec<usize>;
+    // (j, i) が辺であるような j
+    fn in_edges(&self, i: usize) -> Vec<usize>;
+}
+
+// SSet, SMap を実装した各 crate のテストから使う
+#[cfg(feature = "testing")]
+pub mod testing {
+    use super::{Entry, NavigableSSet, RangeSSet, SMap, SSet};
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::ops::Bound;
+
+    // 100 未満の要素を adds 回追加して removes 回削除し、同じ操作をした BTreeSet を返す
+    fn fill<S: SSet<u8>>(
+        set: &mut S,
+        rng: &mut SmallRng,
+        adds: usize,
+        removes: usize,
+    ) -> BTreeSet<u8> {
+        let mut btree_set = BTreeSet::new();
+        for _ in 0..adds {
+            let x = rng.gen_range(0..100);
+            assert_eq!(set.add(x), btree_set.insert(x));
+        }
+        for _ in 0..removes {
+            let x = rng.gen_range(0..100);
+            assert_eq!(set.remove(&x), btree_set.remove(&x));
+        }
+        assert_eq!(set.size(), btree_set.len());
+        btree_set
+    }
+
+    // 空の set を受け取って、昇順に辿れるか調べる
+    pub fn test_iter<S>(mut set: S)
+    where
+        S: SSet<u8> + IntoIterator<Item = u8>,
+        for<'a> &'a S: IntoIterator<Item = &'a u8>,
+    {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let btree_set = fill(&mut set, &mut rng, 100, 50);
+        assert!((&set).into_iter().eq(btree_set.iter()));
+        assert!(set.into_iter().eq(btree_set.into_iter()));
+    }
+
+    // 空の set を受け取って、BTreeSet::range と同じ結果になるか調べる
+    pub fn test_range<S>(mut set: S)
+    where
+        for<'a> S: RangeSSet<'a, u8>,
+    {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let btree_set = fill(&mut set, &mut rng, 50, 0);
+        assert!(set.range(..).eq(btree_set.range(..)));
+        for _ in 0..100 {
+            let a = rng.gen_range(0..110);
+            let b = rng.gen_range(a..=110);
+            assert!(set.range(a..b).eq(btree_set.range(a..b)));
+            assert!(set.range(a..=b).eq(btree_set.range(a..=b)));
+            assert!(set.range(a..).eq(btree_set.range(a..)));
+            assert!(set.range(..b).eq(btree_set.range(..b)));
+            assert!(set.range(..=b).eq(btree_set.range(..=b)));
+            let r = (Bound::Excluded(a), Bound::Included(b));
+            assert!(set.range(r).eq(btree_set.range(r)));
+        }
+        let r = (Bound::Included(60), Bound::Excluded(40));
+        assert_eq!(set.range(r).next(), None);
+        let r = (Bound::Excluded(5), Bound::Excluded(5));
+        assert_eq!(set.range(r).next(), None);
+        set.add(u8::MAX);
+        let r = (Bound::Excluded(u8::MAX), Bound::Unbounded);
+        assert_eq!(set.range(r).next(), None);
+        assert_eq!(set.range(u8::MAX..=u8::MAX).next(), Some(&u8::MAX));
+    }
+
+    // 空の set を受け取って、find_gt などが BTreeSet と同じ結果になるか調べる
+    pub fn test_navigate<S>(mut set: S)
+    where
+        S: NavigableSSet<u8>,
+    {
+        let mut rng = SmallRng::seed_from_u64(0);
+        assert_eq!(set.first(), None);
+        assert_eq!(set.last(), None);
+        let btree_set = fill(&mut set, &mut rng, 50, 0);
+        assert_eq!(set.first(), btree_set.iter().next());
+        assert_eq!(set.last(), btree_set.iter().next_back());
+        for x in 0..110 {
+            assert_eq!(set.find_gt(&x), btree_set.range(x + 1..).next());
+            assert_eq!(set.find_le(&x), btree_set.range(..=x).next_back());
+            assert_eq!(set.find_lt(&x), btree_set.range(..x).next_back());
+        }
+        set.add(u8::MAX);
+        assert_eq!(set.find_gt(&u8::MAX), None);
+        assert_eq!(set.find_le(&u8::MAX), Some(&u8::MAX));
+    }
+
+    // 空の set と、set の rank, select を受け取って調べる
+    // rank(x) は x より小さい要素の個数, select(k) は k 番目 (0-indexed) に小さい要素
+    pub fn test_rank_select<S, R, L>(mut set: S, rank: R, select: L)
+    where
+        S: SSet<u8>,
+        R: Fn(&S, &u8) -> usize,
+        L: for<'a> Fn(&'a S, usize) -> Option<&'a u8>,
+    {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut btree_set = BTreeSet::new();
+        // 昇順に追加すると ScapegoatTree は作り直しが起きる
+        for x in 0..100 {
+            set.add(x);
+            btree_set.insert(x);
+        }
+        for _ in 0..80 {
+            let x = rng.gen_range(0..100);
+            assert_eq!(set.remove(&x), btree_set.remove(&x));
+        }
+        for x in 0..110 {
+            assert_eq!(rank(&set, &x), btree_set.range(..x).count());
+        }
+        for k in 0..110 {
+            assert_eq!(select(&set, k), btree_set.iter().nth(k));
+        }
+    }
+
+    // BTreeMap と同じ結果になるか調べる
+    pub fn test_map_random<M>(mut map: M)
+    where
+        M: SMap<u32, u32>,
+    {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut btree_map = BTreeMap::new();
+        for _ in 0..1000 {
+            let key = rng.gen_range(0..100);
+            match rng.gen_range(0..4) {
+                0 => {
+                    let value = rng.gen::<u32>();
+                    assert_eq!(map.insert(key, value), btree_map.insert(key, value));
+                }
+                1 => {
+                    assert_eq!(map.remove(&key), btree_map.remove(&key));
+                }
+                2 => {
+                    if let Some(v) = map.get_mut(&key) {
+                        *v = v.wrapping_add(1);
+                    }
+                    if let Some(v) = btree_map.get_mut(&key) {
+                        *v = v.wrapping_add(1);
+                    }
+                }
+                _ => {
+                    assert_eq!(map.find(&key), btree_map.range(key..).next());
+                }
+            }
+            assert_eq!(map.get(&key), btree_map.get(&key));
+            assert_eq!(map.size(), btree_map.len());
+        }
+    }
+
+    pub fn test_map_entry<M>(mut map: M)
+    where
+        M: SMap<char, i32>,
+    {
+        for c in "abracadabra".chars() {
+            *map.entry(c).or_insert(0) += 1;
+        }
+        assert_eq!(map.get(&'a'), Some(&5));
+        assert_eq!(map.get(&'b'), Some(&2));
+        assert_eq!(map.get(&'r'), Some(&2));
+        assert_eq!(map.get(&'z'), None);
+        assert_eq!(map.size(), 5);
+
+        map.entry('a').and_modify(|v| *v *= 10).or_insert(0);
+        assert_eq!(map.get(&'a'), Some(&50));
+
+        match map.entry('z') {
+            Entry::Occupied(_) => unreachable!(),
+            Entry::Vacant(e) => {
+                assert_eq!(e.key(), &'z');
+                assert_eq!(e.insert(26), &mut 26);
+            }
+        }
+        match map.entry('z') {
+            Entry::Occupied(mut e) => {
+                assert_eq!(e.insert(0), 26);
+                assert_eq!(e.get(), &0);
+            }
+            Entry::Vacant(_) => unreachable!(),
+        }
+        assert_eq!(map.remove(&'z'), Some(0));
+        assert_eq!(map.remove(&'z'), None);
+    }
+}
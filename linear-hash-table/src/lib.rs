@@ -0,0 +1,236 @@
+use std::mem;
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use binary_trie::IntValue;
+use interface::USet;
+
+enum Slot<T> {
+    Null,
+    Del, // 削除済み
+    Val(T),
+}
+
+pub struct LinearHashTable<T> {
+    t: Vec<Slot<T>>, // t.len() = 2^d
+    n: usize,        // Val の個数
+    q: usize,        // Val と Del の個数
+    d: u32,
+    tab: [[u64; 256]; 8], // 8 bit ずつに分けてハッシュ値を決める
+}
+
+impl<T> LinearHashTable<T>
+where
+    T: IntValue,
+{
+    // tab はインスタンスごとに OS の乱数から決める
+    pub fn new() -> Self {
+        Self::with_rng(SmallRng::from_entropy())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(SmallRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(mut rng: SmallRng) -> Self {
+        let mut tab = [[0; 256]; 8];
+        for row in tab.iter_mut() {
+            rng.fill(&mut row[..]);
+        }
+        Self {
+            t: Self::alloc_table(1),
+            n: 0,
+            q: 0,
+            d: 1,
+            tab,
+        }
+    }
+
+    fn alloc_table(d: u32) -> Vec<Slot<T>> {
+        (0..(1 << d)).map(|_| Slot::Null).collect()
+    }
+
+    // tabulation hashing
+    fn hash(&self, x: &T) -> usize {
+        let h = x.int_value();
+        let mut v = 0;
+        for (i, row) in self.tab.iter().enumerate() {
+            v ^= row[(h >> (8 * i) & 0xff) as usize];
+        }
+        (v >> (u64::BITS - self.d)) as usize
+    }
+
+    // 3n <= 2^d となる最小の d で作り直す
+    // O(n) time
+    fn resize(&mut self) {
+        let mut d = 1;
+        while (1 << d) < self.n * 3 {
+            d += 1;
+        }
+        let old = mem::replace(&mut self.t, Self::alloc_table(d));
+        self.d = d;
+        self.q = self.n;
+        for slot in old {
+            if let Slot::Val(x) = slot {
+                let mut i = self.hash(&x);
+                while let Slot::Val(_) = self.t[i] {
+                    i = (i + 1) & (self.t.len() - 1);
+                }
+                self.t[i] = Slot::Val(x);
+            }
+        }
+    }
+
+    // x を持つ位置を返す
+    // x がなければ探索が止まった Null の位置を Err で返す
+    // expected O(1) time
+    fn find_index(&self, x: &T) -> Result<usize, usize> {
+        let ix = x.int_value();
+        let mut i = self.hash(x);
+        loop {
+            match &self.t[i] {
+                Slot::Null => return Err(i),
+                Slot::Val(y) if y.int_value() == ix => return Ok(i),
+                _ => {}
+            }
+            i = (i + 1) & (self.t.len() - 1);
+        }
+    }
+
+    // find(x) で調べるマスの個数
+    // 線形探索の長さを計測するために使う
+    pub fn probe_length(&self, x: &T) -> usize {
+        let i = self.hash(x);
+        let j = match self.find_index(x) {
+            Ok(j) => j,
+            Err(j) => j,
+        };
+        // 末尾から先頭に回り込むことがある
+        ((j + self.t.len() - i) & (self.t.len() - 1)) + 1
+    }
+}
+
+impl<T> Default for LinearHashTable<T>
+where
+    T: IntValue,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> USet<T> for LinearHashTable<T>
+where
+    T: IntValue,
+{
+    fn size(&self) -> usize {
+        self.n
+    }
+
+    // amortized expected O(1) time
+    fn add(&mut self, x: T) -> bool {
+        if self.find_index(&x).is_ok() {
+            return false;
+        }
+        if (self.q + 1) * 2 > self.t.len() {
+            self.resize();
+        }
+        let mut i = self.hash(&x);
+        while let Slot::Val(_) = self.t[i] {
+            i = (i + 1) & (self.t.len() - 1);
+        }
+        if let Slot::Null = self.t[i] {
+            self.q += 1;
+        }
+        self.n += 1;
+        self.t[i] = Slot::Val(x);
+        true
+    }
+
+    // amortized expected O(1) time
+    fn remove(&mut self, x: &T) -> bool {
+        match self.find_index(x) {
+            Ok(i) => {
+                self.t[i] = Slot::Del;
+                self.n -= 1;
+                if self.n * 8 < self.t.len() {
+                    self.resize();
+                }
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    // expected O(1) time
+    fn find(&self, x: &T) -> Option<&T> {
+        match self.find_index(x) {
+            Ok(i) => match &self.t[i] {
+                Slot::Val(y) => Some(y),
+                _ => unreachable!(),
+            },
+            Err(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LinearHashTable, Slot};
+    use interface::USet;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_add_remove() {
+        let mut table = LinearHashTable::new();
+        assert!(table.add('a'));
+        assert!(!table.add('a'));
+        assert!(table.add('b'));
+        assert_eq!(table.size(), 2);
+        assert_eq!(table.find(&'a'), Some(&'a'));
+        assert_eq!(table.find(&'c'), None);
+        assert!(table.remove(&'a'));
+        assert!(!table.remove(&'a'));
+        assert_eq!(table.find(&'a'), None);
+        assert_eq!(table.size(), 1);
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut table = LinearHashTable::with_seed(0);
+        let mut hash_set = HashSet::new();
+        for _ in 0..1000 {
+            let x = rng.gen_range(0..200_u32);
+            match rng.gen_range(0..3) {
+                0 => {
+                    assert_eq!(table.add(x), hash_set.insert(x));
+                }
+                1 => {
+                    assert_eq!(table.remove(&x), hash_set.remove(&x));
+                }
+                _ => {
+                    assert_eq!(table.find(&x), hash_set.get(&x));
+                }
+            }
+            assert_eq!(table.size(), hash_set.len());
+            // Null が半分以上残っているので探索は必ず止まる
+            let q = table.t.iter().filter(|s| !matches!(s, Slot::Null)).count();
+            assert_eq!(table.q, q);
+            assert!(table.q * 2 <= table.t.len());
+        }
+    }
+
+    #[test]
+    fn test_probe_length() {
+        let mut table = LinearHashTable::with_seed(0);
+        for x in 0..1000_u32 {
+            table.add(x);
+        }
+        let total: usize = (0..1000_u32).map(|x| table.probe_length(&x)).sum();
+        assert!((0..1000_u32).all(|x| table.probe_length(&x) >= 1));
+        // 1 回の探索で調べるマスは平均して定数個
+        assert!(total < 1000 * 4);
+    }
+}
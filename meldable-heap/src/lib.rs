@@ -0,0 +1,253 @@
+use rand::{rngs::SmallRng, RngCore, SeedableRng};
+use std::ptr;
+
+use interface::PriorityQueue;
+
+struct Node<T> {
+    x: T,
+    left: *mut Node<T>,
+    right: *mut Node<T>,
+}
+
+// 親は子以下の値を持つ二分木
+// merge で左右どちらの子に進むかをランダムに決める
+pub struct MeldableHeap<T, R = SmallRng> {
+    n: usize,
+    root: *mut Node<T>,
+    rng: R,
+}
+
+impl<T> MeldableHeap<T> {
+    // seed はインスタンスごとに OS の乱数から決める
+    pub fn new() -> Self {
+        Self::with_rng(SmallRng::from_entropy())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(SmallRng::seed_from_u64(seed))
+    }
+}
+
+impl<T> Default for MeldableHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, R> MeldableHeap<T, R>
+where
+    R: RngCore,
+{
+    pub fn with_rng(rng: R) -> Self {
+        Self {
+            n: 0,
+            root: ptr::null_mut(),
+            rng,
+        }
+    }
+}
+
+impl<T, R> MeldableHeap<T, R>
+where
+    T: Ord,
+    R: RngCore,
+{
+    // h1 と h2 を根とするヒープをまとめて、その根を返す
+    // expected O(log n) time
+    fn merge(&mut self, h1: *mut Node<T>, h2: *mut Node<T>) -> *mut Node<T> {
+        if h1.is_null() {
+            return h2;
+        }
+        if h2.is_null() {
+            return h1;
+        }
+        let (h1, h2) = if unsafe { &(*h2).x } < unsafe { &(*h1).x } {
+            (h2, h1)
+        } else {
+            (h1, h2)
+        };
+        if self.rng.next_u32() & 1 == 0 {
+            let left = unsafe { &*h1 }.left;
+            let u = self.merge(left, h2);
+            unsafe { (*h1).left = u };
+        } else {
+            let right = unsafe { &*h1 }.right;
+            let u = self.merge(right, h2);
+            unsafe { (*h1).right = u };
+        }
+        h1
+    }
+
+    // other の要素をすべて self に移す
+    // expected O(log n + log m) time
+    pub fn meld<S>(&mut self, mut other: MeldableHeap<T, S>) {
+        let h2 = other.root;
+        // other の drop でノードが解放されないようにする
+        other.root = ptr::null_mut();
+        self.root = self.merge(self.root, h2);
+        self.n += other.n;
+        other.n = 0;
+    }
+}
+
+impl<T, R> PriorityQueue<T> for MeldableHeap<T, R>
+where
+    T: Ord,
+    R: RngCore,
+{
+    fn size(&self) -> usize {
+        self.n
+    }
+
+    // expected O(log n) time
+    fn add(&mut self, x: T) {
+        let u = Box::into_raw(Box::new(Node {
+            x,
+            left: ptr::null_mut(),
+            right: ptr::null_mut(),
+        }));
+        self.root = self.merge(self.root, u);
+        self.n += 1;
+    }
+
+    // expected O(log n) time
+    fn remove_min(&mut self) -> Option<T> {
+        if self.root.is_null() {
+            return None;
+        }
+        let u = unsafe { Box::from_raw(self.root) };
+        self.root = self.merge(u.left, u.right);
+        self.n -= 1;
+        Some(u.x)
+    }
+
+    // O(1) time
+    fn peek_min(&self) -> Option<&T> {
+        if self.root.is_null() {
+            None
+        } else {
+            Some(&unsafe { &*self.root }.x)
+        }
+    }
+}
+
+impl<T, R> MeldableHeap<T, R> {
+    pub fn height(&self) -> usize {
+        let mut max_depth = 0;
+        let mut stack = vec![(self.root, 0)];
+        while let Some((u, d)) = stack.pop() {
+            if u.is_null() {
+                continue;
+            }
+            max_depth = max_depth.max(d);
+            stack.push((unsafe { &*u }.left, d + 1));
+            stack.push((unsafe { &*u }.right, d + 1));
+        }
+        max_depth
+    }
+}
+
+impl<T, R> Drop for MeldableHeap<T, R> {
+    fn drop(&mut self) {
+        let mut stack = vec![self.root];
+        while let Some(u) = stack.pop() {
+            if u.is_null() {
+                continue;
+            }
+            let u = unsafe { Box::from_raw(u) };
+            stack.push(u.left);
+            stack.push(u.right);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MeldableHeap;
+    use interface::PriorityQueue;
+    use rand::rngs::{mock::StepRng, SmallRng};
+    use rand::{Rng, SeedableRng};
+    use std::cmp::Reverse;
+    use std::collections::BinaryHeap;
+
+    #[test]
+    fn test_add_remove() {
+        let mut heap = MeldableHeap::new();
+        assert_eq!(heap.peek_min(), None);
+        assert_eq!(heap.remove_min(), None);
+        heap.add(3);
+        heap.add(1);
+        heap.add(2);
+        assert_eq!(heap.peek_min(), Some(&1));
+        assert_eq!(heap.remove_min(), Some(1));
+        assert_eq!(heap.remove_min(), Some(2));
+        heap.add(0);
+        assert_eq!(heap.remove_min(), Some(0));
+        assert_eq!(heap.remove_min(), Some(3));
+        assert_eq!(heap.remove_min(), None);
+        assert_eq!(heap.size(), 0);
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut heap = MeldableHeap::with_seed(0);
+        let mut std_heap = BinaryHeap::new();
+        for _ in 0..1000 {
+            if rng.gen_bool(0.6) {
+                let x = rng.gen_range(0..100);
+                heap.add(x);
+                std_heap.push(Reverse(x));
+            } else {
+                assert_eq!(heap.remove_min(), std_heap.pop().map(|Reverse(x)| x));
+            }
+            assert_eq!(heap.size(), std_heap.len());
+            assert_eq!(heap.peek_min(), std_heap.peek().map(|Reverse(x)| x));
+        }
+    }
+
+    #[test]
+    fn test_meld() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut heap = MeldableHeap::with_seed(0);
+        let mut std_heap = BinaryHeap::new();
+        for _ in 0..100 {
+            let mut other = MeldableHeap::with_seed(rng.gen());
+            for _ in 0..rng.gen_range(0..20) {
+                let x = rng.gen_range(0..1000);
+                other.add(x);
+                std_heap.push(Reverse(x));
+            }
+            heap.meld(other);
+            for _ in 0..rng.gen_range(0..10) {
+                assert_eq!(heap.remove_min(), std_heap.pop().map(|Reverse(x)| x));
+            }
+            assert_eq!(heap.size(), std_heap.len());
+        }
+        while let Some(Reverse(x)) = std_heap.pop() {
+            assert_eq!(heap.remove_min(), Some(x));
+        }
+        assert_eq!(heap.remove_min(), None);
+    }
+
+    #[test]
+    fn test_with_seed() {
+        let mut heap_1 = MeldableHeap::with_seed(42);
+        let mut heap_2 = MeldableHeap::with_seed(42);
+        for x in 0..100 {
+            heap_1.add(x);
+            heap_2.add(x);
+        }
+        assert_eq!(heap_1.height(), heap_2.height());
+    }
+
+    #[test]
+    fn test_with_rng() {
+        // 常に左の子に進むので昇順に追加すると一直線になる
+        let mut heap = MeldableHeap::with_rng(StepRng::new(0, 0));
+        for x in 0..100 {
+            heap.add(x);
+        }
+        assert_eq!(heap.height(), 99);
+    }
+}
@@ -0,0 +1,342 @@
+use std::{cmp, mem, ptr};
+
+use binary_search_tree as bst;
+use interface::SSet;
+
+// data は色
+// 削除の途中では黒が 2 つ重なった 2 にもなる
+type Node<T> = bst::Node<T, u8>;
+
+const RED: u8 = 0;
+const BLACK: u8 = 1;
+
+// 左傾赤黒木
+// - 根から葉 (NULL) までの経路にある黒いノードの数はどれも等しい
+// - 赤いノードが親子で並ばない
+// - 右の子が赤なら左の子も赤
+pub struct RedBlackTree<T> {
+    n: usize,
+    root: *mut Node<T>,
+}
+
+impl<T> RedBlackTree<T> {
+    pub fn new() -> Self {
+        Self {
+            n: 0,
+            root: ptr::null_mut(),
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        unsafe { bst::height(self.root) }
+    }
+
+    // NULL は黒
+    fn colour(u: *mut Node<T>) -> u8 {
+        if u.is_null() {
+            BLACK
+        } else {
+            unsafe { &*u }.data
+        }
+    }
+
+    // 削除の途中で NULL の色を変えることがあるが、その後 NULL の色は見ないので無視する
+    fn add_colour(u: *mut Node<T>, c: i8) {
+        if !u.is_null() {
+            unsafe { (*u).data = ((*u).data as i8 + c) as u8 };
+        }
+    }
+
+    // u の黒を子に押し下げる
+    fn push_black(u: *mut Node<T>) {
+        Self::add_colour(u, -1);
+        Self::add_colour(unsafe { &*u }.left, 1);
+        Self::add_colour(unsafe { &*u }.right, 1);
+    }
+
+    // 子の黒を u に引き上げる
+    fn pull_black(u: *mut Node<T>) {
+        Self::add_colour(u, 1);
+        Self::add_colour(unsafe { &*u }.left, -1);
+        Self::add_colour(unsafe { &*u }.right, -1);
+    }
+
+    fn swap_colours(u: *mut Node<T>, w: *mut Node<T>) {
+        unsafe { mem::swap(&mut (*u).data, &mut (*w).data) };
+    }
+
+    // O(1) time
+    fn flip_left(&mut self, u: *mut Node<T>) {
+        Self::swap_colours(u, unsafe { &*u }.right);
+        unsafe { bst::rotate_left(&mut self.root, u) };
+    }
+
+    // O(1) time
+    fn flip_right(&mut self, u: *mut Node<T>) {
+        Self::swap_colours(u, unsafe { &*u }.left);
+        unsafe { bst::rotate_right(&mut self.root, u) };
+    }
+
+    // 赤いノード u を追加したあとに性質を直す
+    // O(log(n)) time
+    fn add_fixup(&mut self, u: *mut Node<T>) {
+        let mut u = u;
+        while Self::colour(u) == RED {
+            if u == self.root {
+                unsafe { (*u).data = BLACK };
+                return;
+            }
+            let mut w = unsafe { &*u }.parent;
+            if Self::colour(unsafe { &*w }.left) == BLACK {
+                // 右の子が赤にならないようにする
+                self.flip_left(w);
+                u = w;
+                w = unsafe { &*u }.parent;
+            }
+            if Self::colour(w) == BLACK {
+                // 赤いノードが並んでいない
+                return;
+            }
+            let g = unsafe { &*w }.parent;
+            if Self::colour(unsafe { &*g }.right) == BLACK {
+                self.flip_right(g);
+                return;
+            }
+            Self::push_black(g);
+            u = g;
+        }
+    }
+
+    // u (親は p) の色が黒 2 つ分になっているのを直す
+    // u が NULL の場合も、u の色が黒 2 つ分であるとして扱う
+    // O(log(n)) time
+    fn remove_fixup(&mut self, u: *mut Node<T>, p: *mut Node<T>) {
+        let mut u = u;
+        let mut p = p;
+        while u.is_null() || unsafe { &*u }.data > BLACK {
+            if p.is_null() {
+                // u は根
+                if !u.is_null() {
+                    unsafe { (*u).data = BLACK };
+                }
+                break;
+            }
+            if Self::colour(unsafe { &*p }.left) == RED {
+                // u は右の子で、回転しても u の親は p のまま
+                self.flip_right(p);
+                continue;
+            }
+            u = if u == unsafe { &*p }.left {
+                self.remove_fixup_case2(p)
+            } else {
+                self.remove_fixup_case3(p)
+            };
+            p = unsafe { &*u }.parent;
+        }
+        self.lean_left(p);
+    }
+
+    // w の右の子だけが赤になっていたら左傾にする
+    // O(1) time
+    fn lean_left(&mut self, w: *mut Node<T>) {
+        if w.is_null() {
+            return;
+        }
+        if Self::colour(unsafe { &*w }.right) == RED && Self::colour(unsafe { &*w }.left) == BLACK {
+            self.flip_left(w);
+        }
+    }
+
+    // u = w.left のとき
+    fn remove_fixup_case2(&mut self, w: *mut Node<T>) -> *mut Node<T> {
+        let v = unsafe { &*w }.right;
+        Self::pull_black(w);
+        self.flip_left(w);
+        let q = unsafe { &*w }.right;
+        if Self::colour(q) == RED {
+            // q と w が赤で並んでいる
+            unsafe { bst::rotate_left(&mut self.root, w) };
+            self.flip_right(v);
+            Self::push_black(q);
+            if Self::colour(unsafe { &*v }.right) == RED {
+                self.flip_left(v);
+            }
+            q
+        } else {
+            v
+        }
+    }
+
+    // u = w.right のとき
+    fn remove_fixup_case3(&mut self, w: *mut Node<T>) -> *mut Node<T> {
+        let v = unsafe { &*w }.left;
+        Self::pull_black(w);
+        self.flip_right(w);
+        let q = unsafe { &*w }.left;
+        if Self::colour(q) == RED {
+            // q と w が赤で並んでいる
+            unsafe { bst::rotate_right(&mut self.root, w) };
+            self.flip_left(v);
+            Self::push_black(q);
+            q
+        } else if Self::colour(unsafe { &*v }.left) == RED {
+            // v の子がどちらも赤
+            Self::push_black(v);
+            v
+        } else {
+            // 左傾にする
+            self.flip_left(v);
+            w
+        }
+    }
+}
+
+impl<T> Default for RedBlackTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SSet<T> for RedBlackTree<T>
+where
+    T: cmp::Ord,
+{
+    // O(1) time
+    fn size(&self) -> usize {
+        self.n
+    }
+
+    // O(log(n)) time
+    fn add(&mut self, x: T) -> bool {
+        let p = unsafe { bst::find_last(self.root, &x) };
+        if !p.is_null() && unsafe { &*p }.x == x {
+            return false;
+        }
+        let u = Box::into_raw(Box::new(Node::new(x, RED)));
+        unsafe { bst::add_child(&mut self.root, p, u) };
+        self.add_fixup(u);
+        self.n += 1;
+        true
+    }
+
+    // O(log(n)) time
+    fn remove(&mut self, x: &T) -> bool {
+        let u = unsafe { bst::find_last(self.root, x) };
+        if u.is_null() || unsafe { &*u }.x != *x {
+            return false;
+        }
+        // 子が 1 つ以下のノード w を外す
+        // u が右の子を持つときは右の部分木の最小の要素と入れ替えてから外す
+        let right = unsafe { &*u }.right;
+        let (w, child) = if right.is_null() {
+            (u, unsafe { &*u }.left)
+        } else {
+            let w = unsafe { bst::first_node(right) };
+            unsafe { mem::swap(&mut (*u).x, &mut (*w).x) };
+            (w, unsafe { &*w }.right)
+        };
+        let p = unsafe { &*w }.parent;
+        unsafe { bst::remove_node(&mut self.root, w) };
+        let w = unsafe { Box::from_raw(w) };
+        self.n -= 1;
+        if child.is_null() {
+            if w.data == BLACK {
+                self.remove_fixup(child, p);
+            } else {
+                // 赤い葉を外したときは黒い高さは変わらない
+                self.lean_left(p);
+            }
+        } else {
+            Self::add_colour(child, w.data as i8);
+            self.remove_fixup(child, p);
+        }
+        true
+    }
+
+    // O(log(n)) time
+    fn find(&self, x: &T) -> Option<&T> {
+        let u = unsafe { bst::partition_point_node(self.root, |y| y < x) };
+        unsafe { u.as_ref() }.map(|u| &u.x)
+    }
+}
+
+impl<T> Drop for RedBlackTree<T> {
+    fn drop(&mut self) {
+        unsafe { bst::drop_tree(self.root) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bst, Node, RedBlackTree, BLACK, RED};
+    use interface::SSet;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+    use std::collections::BTreeSet;
+
+    // 二分探索木の性質, 赤黒木の性質, parent, size が正しいか調べる
+    fn validate<T: Ord>(tree: &RedBlackTree<T>) {
+        // 黒い高さを返す
+        fn black_height<T>(u: *mut Node<T>) -> usize {
+            if u.is_null() {
+                return 1;
+            }
+            let node = unsafe { &*u };
+            let left = black_height(node.left);
+            assert_eq!(left, black_height(node.right));
+            left + node.data as usize
+        }
+        let check = |node: &Node<T>| {
+            assert!(node.data == RED || node.data == BLACK);
+            for &c in &[node.left, node.right] {
+                if !c.is_null() {
+                    assert!(node.data == BLACK || unsafe { &*c }.data == BLACK);
+                }
+            }
+            // 左傾
+            assert!(RedBlackTree::colour(node.left) == RED || RedBlackTree::colour(node.right) == BLACK);
+        };
+        assert_eq!(
+            unsafe { bst::testing::validate(tree.root, check) },
+            tree.size()
+        );
+        assert_eq!(RedBlackTree::<T>::colour(tree.root), BLACK);
+        black_height(tree.root);
+    }
+
+    #[test]
+    fn test_sorted() {
+        let mut tree = RedBlackTree::new();
+        for x in 0..1000 {
+            assert!(tree.add(x));
+            validate(&tree);
+        }
+        assert!(!tree.add(0));
+        // 高さは 2 log(n) 以下
+        assert!(tree.height() <= 20);
+        for x in 0..1000 {
+            assert!(tree.remove(&x));
+            validate(&tree);
+        }
+        assert!(!tree.remove(&0));
+        assert_eq!(tree.size(), 0);
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut tree = RedBlackTree::new();
+        let mut btree_set = BTreeSet::new();
+        for _ in 0..3000 {
+            let x = rng.gen_range(0..200);
+            if rng.gen_bool(0.5) {
+                assert_eq!(tree.add(x), btree_set.insert(x));
+            } else {
+                assert_eq!(tree.remove(&x), btree_set.remove(&x));
+            }
+            assert_eq!(tree.size(), btree_set.len());
+            let x = rng.gen_range(0..210);
+            assert_eq!(tree.find(&x), btree_set.range(x..).next());
+            validate(&tree);
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use array_stack::alloc_array;
+use interface::{List, Stack};
+
+// b 番目のブロックの長さは b+1
+// r 個のブロックに r(r+1)/2 個の要素を入れられる
+pub struct RootishArrayStack<T> {
+    blocks: Vec<*mut [Option<T>]>,
+    n: usize,
+}
+
+impl<T> RootishArrayStack<T> {
+    pub fn new() -> Self {
+        Self {
+            blocks: Vec::new(),
+            n: 0,
+        }
+    }
+
+    // i 番目の要素が入るブロックの番号
+    // b(b+1)/2 <= i < (b+1)(b+2)/2 を満たす b
+    fn i2b(i: usize) -> usize {
+        let db = (-3.0 + (9.0 + 8.0 * i as f64).sqrt()) / 2.0;
+        db.ceil() as usize
+    }
+
+    // i 番目の要素は b 番目のブロックの j 番目に入る
+    fn position(i: usize) -> (usize, usize) {
+        let b = Self::i2b(i);
+        let j = i - b * (b + 1) / 2;
+        debug_assert!(j <= b);
+        (b, j)
+    }
+
+    fn block_slot(&self, b: usize, j: usize) -> *mut Option<T> {
+        unsafe { (self.blocks[b] as *mut Option<T>).add(j) }
+    }
+
+    fn slot(&self, i: usize) -> *mut Option<T> {
+        let (b, j) = Self::position(i);
+        self.block_slot(b, j)
+    }
+
+    // O(1) time
+    fn grow(&mut self) {
+        let r = self.blocks.len();
+        self.blocks.push(alloc_array(r + 1));
+    }
+
+    // 空きが多すぎるときに末尾のブロックを解放する
+    // O(√n) time
+    fn shrink(&mut self) {
+        let mut r = self.blocks.len();
+        while r > 0 && r.saturating_sub(2) * (r - 1) / 2 >= self.n {
+            let block = self.blocks.pop().unwrap();
+            drop(unsafe { Box::from_raw(block) });
+            r -= 1;
+        }
+    }
+}
+
+impl<T> Default for RootishArrayStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> List<T> for RootishArrayStack<T> {
+    fn size(&self) -> usize {
+        self.n
+    }
+
+    // O(1) time
+    fn get(&self, i: usize) -> Option<&T> {
+        if i < self.n {
+            unsafe { &*self.slot(i) }.as_ref()
+        } else {
+            None
+        }
+    }
+
+    // O(1) time
+    fn set(&self, i: usize, x: T) -> T {
+        assert!(i < self.n, "expect `i` < RootishArrayStack::size()");
+        let y = unsafe { (*self.slot(i)).replace(x) };
+        y.unwrap()
+    }
+
+    // amortized O(1 + n-i) time
+    fn add(&mut self, i: usize, x: T) {
+        assert!(i <= self.n, "expect `i` <= RootishArrayStack::size()");
+        let r = self.blocks.len();
+        if r * (r + 1) / 2 < self.n + 1 {
+            self.grow();
+        }
+        self.n += 1;
+        // 要素をひとつずつ後ろにずらす
+        // 毎回 i2b で位置を計算すると遅いので、ブロック内の位置を辿っていく
+        let (mut b, mut j) = Self::position(self.n - 1);
+        for _ in (i + 1)..self.n {
+            let (pb, pj) = if j == 0 { (b - 1, b - 1) } else { (b, j - 1) };
+            unsafe { *self.block_slot(b, j) = (*self.block_slot(pb, pj)).take() };
+            b = pb;
+            j = pj;
+        }
+        unsafe { *self.block_slot(b, j) = Some(x) };
+    }
+
+    // amortized O(1 + n-i) time
+    fn remove(&mut self, i: usize) -> T {
+        assert!(i < self.n, "expect `i` < RootishArrayStack::size()");
+        let (mut b, mut j) = Self::position(i);
+        let x = unsafe { (*self.block_slot(b, j)).take() };
+        // 要素をひとつずつ前にずらす
+        for _ in i..(self.n - 1) {
+            let (nb, nj) = if j == b { (b + 1, 0) } else { (b, j + 1) };
+            unsafe { *self.block_slot(b, j) = (*self.block_slot(nb, nj)).take() };
+            b = nb;
+            j = nj;
+        }
+        self.n -= 1;
+        let r = self.blocks.len();
+        if r.saturating_sub(2) * (r - 1) / 2 >= self.n {
+            self.shrink();
+        }
+        x.unwrap()
+    }
+}
+
+impl<T> Stack<T> for RootishArrayStack<T> {
+    // amortized O(1) time
+    fn push(&mut self, x: T) {
+        self.add(self.n, x);
+    }
+
+    // amortized O(1) time
+    fn pop(&mut self) -> Option<T> {
+        if self.n == 0 {
+            None
+        } else {
+            Some(self.remove(self.n - 1))
+        }
+    }
+}
+
+impl<T> Drop for RootishArrayStack<T> {
+    fn drop(&mut self) {
+        for &block in &self.blocks {
+            drop(unsafe { Box::from_raw(block) });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RootishArrayStack;
+    use interface::{List, Stack};
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+    #[test]
+    fn test_i2b() {
+        // 0 | 1 2 | 3 4 5 | 6 7 8 9 | ...
+        let b: Vec<usize> = (0..10).map(RootishArrayStack::<()>::i2b).collect();
+        assert_eq!(b, vec![0, 1, 1, 2, 2, 2, 3, 3, 3, 3]);
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let mut stack = RootishArrayStack::new();
+        assert_eq!(stack.pop(), None);
+        stack.push('a');
+        stack.push('b');
+        stack.push('c');
+        assert_eq!(stack.set(1, 'x'), 'b');
+        assert_eq!(stack.get(1), Some(&'x'));
+        assert_eq!(stack.pop(), Some('c'));
+        assert_eq!(stack.pop(), Some('x'));
+        assert_eq!(stack.pop(), Some('a'));
+        assert_eq!(stack.pop(), None);
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut v = Vec::new();
+        let mut list = RootishArrayStack::new();
+        for _ in 0..1000 {
+            if v.is_empty() || rng.gen_bool(0.6) {
+                let i = rng.gen_range(0..=v.len());
+                let x = rng.gen::<u32>();
+                v.insert(i, x);
+                list.add(i, x);
+            } else {
+                let i = rng.gen_range(0..v.len());
+                assert_eq!(list.remove(i), v.remove(i));
+            }
+            assert_eq!(list.size(), v.len());
+            // 空きは O(√n) に抑えられる
+            let r = list.blocks.len();
+            assert!(r * (r + 1) / 2 >= v.len());
+            assert!(r < 3 || (r - 2) * (r - 1) / 2 < v.len());
+        }
+        for i in 0..v.len() {
+            assert_eq!(list.get(i), v.get(i));
+        }
+    }
+}
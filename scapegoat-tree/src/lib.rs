@@ -1,17 +1,15 @@
 use std::{
-    alloc,
-    cmp::{self, Ordering},
+    cmp,
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
     ptr,
+    vec,
 };
 
-use interface::SSet;
+use binary_search_tree as bst;
+use interface::{NavigableSSet, Pair, PairMap, PairSet, RangeSSet, SSet};
 
-struct Node<T> {
-    x: T,
-    parent: *mut Node<T>,
-    left: *mut Node<T>,
-    right: *mut Node<T>,
-}
+type Node<T> = bst::Node<T>;
 
 pub struct ScapegoatTree<T> {
     n: usize,
@@ -28,13 +26,11 @@ impl<T> ScapegoatTree<T> {
         }
     }
 
-    // O(n) time
-    fn size_u(u: *mut Node<T>) -> usize {
-        if u == ptr::null_mut() {
-            0
-        } else {
-            1 + Self::size_u(unsafe { &*u }.left) + Self::size_u(unsafe { &*u }.right)
-        }
+    // 小さいほうから k 番目 (0-indexed) の要素を返す
+    // O(log(n)) time
+    pub fn select(&self, k: usize) -> Option<&T> {
+        let u = unsafe { bst::select(self.root, k) };
+        unsafe { u.as_ref() }.map(|u| &u.x)
     }
 
     // u を根とする部分木を完全二分木にする
@@ -81,6 +77,7 @@ impl<T> ScapegoatTree<T> {
             return ptr::null_mut();
         }
         let m = nodes.len() / 2;
+        unsafe { (*nodes[m]).size = nodes.len() };
         let left = Self::build_balanced(&nodes[..m]);
         unsafe { (*nodes[m]).left = left };
         if left != ptr::null_mut() {
@@ -93,6 +90,40 @@ impl<T> ScapegoatTree<T> {
         }
         nodes[m]
     }
+
+    // O(log(n)) time
+    fn first_node(&self) -> *mut Node<T> {
+        unsafe { bst::first_node(self.root) }
+    }
+
+    // O(log(n)) time
+    fn partition_point_node<F>(&self, pred: F) -> *mut Node<T>
+    where
+        F: Fn(&T) -> bool,
+    {
+        unsafe { bst::partition_point_node(self.root, pred) }
+    }
+
+    // O(log(n)) time
+    fn find_pred_node_by<F>(&self, pred: F) -> *mut Node<T>
+    where
+        F: Fn(&T) -> bool,
+    {
+        unsafe { bst::find_pred_node_by(self.root, pred) }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            u: self.first_node(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for ScapegoatTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<T> ScapegoatTree<T>
@@ -103,187 +134,50 @@ where
     // すでに scapegoat 木に x が含まれていたら None を返す
     // O(log(n)) time
     fn add_with_depth(&mut self, x: T) -> Option<(*mut Node<T>, usize)> {
-        let mut w = self.root;
-        let mut depth = 0;
-        if w == ptr::null_mut() {
-            self.root = Box::into_raw(Box::new(Node {
-                x,
-                left: ptr::null_mut(),
-                right: ptr::null_mut(),
-                parent: ptr::null_mut(),
-            }));
-            self.n += 1;
-            self.q += 1;
-            return Some((self.root, depth));
-        }
-        loop {
-            debug_assert_ne!(w, ptr::null_mut());
-            let y = &unsafe { &*w }.x;
-            match x.cmp(y) {
-                Ordering::Less => {
-                    let left = unsafe { &*w }.left;
-                    if left == ptr::null_mut() {
-                        let u = Box::into_raw(Box::new(Node {
-                            x,
-                            left: ptr::null_mut(),
-                            right: ptr::null_mut(),
-                            parent: w,
-                        }));
-                        unsafe { (*w).left = u };
-                        self.n += 1;
-                        self.q += 1;
-                        break Some((u, depth));
-                    } else {
-                        w = left;
-                        depth += 1;
-                    }
-                }
-                Ordering::Greater => {
-                    let right = unsafe { &*w }.right;
-                    if right == ptr::null_mut() {
-                        let u = Box::into_raw(Box::new(Node {
-                            x,
-                            left: ptr::null_mut(),
-                            right: ptr::null_mut(),
-                            parent: w,
-                        }));
-                        unsafe { (*w).right = u };
-                        self.n += 1;
-                        self.q += 1;
-                        break Some((u, depth));
-                    } else {
-                        w = right;
-                        depth += 1;
-                    }
-                }
-                Ordering::Equal => {
-                    break None;
-                }
-            }
+        let p = self.find_last(&x);
+        if !p.is_null() && unsafe { &*p }.x == x {
+            return None;
         }
+        let u = Box::into_raw(Box::new(Node::new(x, ())));
+        unsafe { bst::add_child(&mut self.root, p, u) };
+        self.n += 1;
+        self.q += 1;
+        Some((u, unsafe { bst::depth(u) }))
     }
 
-    // copy of Treap::find_last
     // O(log(n)) time
     fn find_last(&self, x: &T) -> *mut Node<T> {
-        let mut w = self.root;
-        let mut prev = ptr::null_mut();
-        while w != ptr::null_mut() {
-            prev = w;
-            match x.cmp(&unsafe { &*w }.x) {
-                Ordering::Less => {
-                    w = unsafe { &*w }.left;
-                }
-                Ordering::Greater => {
-                    w = unsafe { &*w }.right;
-                }
-                Ordering::Equal => {
-                    return w;
-                }
-            }
-        }
-        prev
+        unsafe { bst::find_last(self.root, x) }
     }
 
-    // u を削除する
+    // x より小さい要素の個数を返す
     // O(log(n)) time
-    fn remove_u(&mut self, u: *mut Node<T>) {
-        debug_assert_ne!(u, ptr::null_mut());
-        let left_u = unsafe { &*u }.left;
-        let right_u = unsafe { &*u }.right;
-        let p = unsafe { &*u }.parent;
-        if left_u == ptr::null_mut() && right_u == ptr::null_mut() {
-            if p == ptr::null_mut() {
-                self.root = ptr::null_mut();
-            } else if unsafe { &*p }.left == u {
-                unsafe { (*p).left = ptr::null_mut() };
-            } else {
-                debug_assert_eq!(unsafe { &*p }.right, u);
-                unsafe { (*p).right = ptr::null_mut() };
-            }
-        } else if left_u == ptr::null_mut() || right_u == ptr::null_mut() {
-            let child = if left_u == ptr::null_mut() {
-                right_u
-            } else {
-                left_u
-            };
-            debug_assert_ne!(child, ptr::null_mut());
-            unsafe { (*child).parent = p };
-            if p == ptr::null_mut() {
-                self.root = child;
-            } else if unsafe { &*p }.left == u {
-                unsafe { (*p).left = child };
-            } else {
-                debug_assert_eq!(unsafe { &*p }.right, u);
-                unsafe { (*p).right = child };
-            }
-        } else {
-            debug_assert_ne!(left_u, ptr::null_mut());
-            debug_assert_ne!(right_u, ptr::null_mut());
-            let mut w = right_u;
-            // w.x が u.x より大きい最小の値になるように左の子を辿る
-            loop {
-                let left_w = unsafe { &*w }.left;
-                if left_w == ptr::null_mut() {
-                    break;
-                }
-                w = left_w;
-            }
-            // w を u の位置に持っていく
-            // 関係するポインタを張り替える
-            let p_w = unsafe { &*w }.parent;
-            if unsafe { &*p_w }.left == w {
-                if unsafe { &*w }.right != ptr::null_mut() {
-                    unsafe { (*(*w).right).parent = p_w };
-                }
-                unsafe { (*p_w).left = (*w).right };
-                unsafe { (*w).left = left_u };
-                unsafe { (*w).right = right_u };
-                unsafe { (*w).parent = p };
-                unsafe { (*left_u).parent = w };
-                unsafe { (*right_u).parent = w };
-            } else {
-                debug_assert_eq!(unsafe { &*p_w }.right, w);
-                debug_assert_eq!(p_w, u);
-                debug_assert_eq!(right_u, w);
-                unsafe { (*p_w).right = (*w).right };
-                unsafe { (*w).left = left_u };
-                unsafe { (*w).parent = p };
-                unsafe { (*left_u).parent = w };
-            }
-            if p == ptr::null_mut() {
-                self.root = w;
-            } else if unsafe { &*p }.left == u {
-                unsafe { (*p).left = w };
-            } else {
-                debug_assert_eq!(unsafe { &*p }.right, u);
-                unsafe { (*p).right = w };
-            }
-        }
-        self.n -= 1;
-        unsafe { ptr::drop_in_place(u) };
-        unsafe { alloc::dealloc(u as *mut u8, alloc::Layout::new::<Node<T>>()) };
+    pub fn rank(&self, x: &T) -> usize {
+        unsafe { bst::rank(self.root, x) }
     }
-}
 
-impl<T> SSet<T> for ScapegoatTree<T>
-where
-    T: cmp::Ord,
-{
-    fn size(&self) -> usize {
-        self.n
+    // u を削除して、u が持っていた要素を返す
+    // O(log(n)) time
+    fn remove_u(&mut self, u: *mut Node<T>) -> T {
+        debug_assert!(!u.is_null());
+        unsafe { bst::remove_node(&mut self.root, u) };
+        self.n -= 1;
+        let u = unsafe { Box::from_raw(u) };
+        u.x
     }
 
+    // x を追加して、x を持つノードを返す
+    // x に等しい要素がすでにあるときは何もせずに NULL を返す
     // amortized O(log(n)) time
-    fn add(&mut self, x: T) -> bool {
+    fn add_node(&mut self, x: T) -> *mut Node<T> {
         if let Some((u, depth)) = self.add_with_depth(x) {
             if depth as f64 > (self.q as f64).log(3.0 / 2.0) {
                 let mut w = unsafe { &*u }.parent;
                 loop {
                     debug_assert_ne!(w, ptr::null_mut());
                     debug_assert_ne!(unsafe { &*w }.parent, ptr::null_mut());
-                    let a = Self::size_u(w);
-                    let b = Self::size_u(unsafe { &*w }.parent);
+                    let a = unsafe { bst::size_u(w) };
+                    let b = unsafe { bst::size_u((*w).parent) };
                     // a/b > 2/3
                     if a * 3 > b * 2 {
                         // 補題 8.1 より、いつか loop から抜ける
@@ -293,67 +187,300 @@ where
                 }
                 self.rebuild(unsafe { &*w }.parent);
             }
-            true
+            u
         } else {
-            false
+            ptr::null_mut()
+        }
+    }
+
+    // u を削除して、u が持っていた要素を返す
+    // amortized O(log(n)) time
+    fn remove_node(&mut self, u: *mut Node<T>) -> T {
+        let x = self.remove_u(u);
+        if self.q > self.n * 2 {
+            if self.root != ptr::null_mut() {
+                self.rebuild(self.root);
+            }
+            self.q = self.n;
         }
+        x
+    }
+}
+
+impl<T> SSet<T> for ScapegoatTree<T>
+where
+    T: cmp::Ord,
+{
+    fn size(&self) -> usize {
+        self.n
+    }
+
+    // amortized O(log(n)) time
+    fn add(&mut self, x: T) -> bool {
+        !self.add_node(x).is_null()
     }
 
     // amortized O(log(n)) time
     fn remove(&mut self, x: &T) -> bool {
         let u = self.find_last(x);
         if u != ptr::null_mut() && unsafe { &*u }.x.eq(x) {
-            self.remove_u(u);
-            if self.q > self.n * 2 {
-                if self.root != ptr::null_mut() {
-                    self.rebuild(self.root);
-                }
-                self.q = self.n;
-            }
+            self.remove_node(u);
             true
         } else {
             false
         }
     }
 
-    // copy of Treap::find
     // O(log(n)) time
     fn find(&self, x: &T) -> Option<&T> {
-        let mut w = self.root;
-        let mut z = ptr::null_mut();
-        loop {
-            if w == ptr::null_mut() {
-                break;
-            }
-            let y = &unsafe { &*w }.x;
-            match x.cmp(y) {
-                Ordering::Less => {
-                    z = w;
-                    w = unsafe { &*w }.left;
-                }
-                Ordering::Greater => {
-                    w = unsafe { &*w }.right;
-                }
-                Ordering::Equal => {
-                    return Some(y);
-                }
-            }
+        let u = self.partition_point_node(|y| y < x);
+        unsafe { u.as_ref() }.map(|u| &u.x)
+    }
+}
+
+pub struct Iter<'a, T> {
+    u: *mut Node<T>,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.u.is_null() {
+            return None;
         }
+        let x = &unsafe { &*self.u }.x;
+        self.u = unsafe { bst::next_node(self.u) };
+        Some(x)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ScapegoatTree<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct IntoIter<T> {
+    nodes: vec::IntoIter<*mut Node<T>>,
+}
 
-        if z == ptr::null_mut() {
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let u = self.nodes.next()?;
+        let u = unsafe { Box::from_raw(u) };
+        Some(u.x)
+    }
+}
+
+impl<T> Drop for IntoIter<T> {
+    fn drop(&mut self) {
+        for _ in self {}
+    }
+}
+
+impl<T> IntoIterator for ScapegoatTree<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        let nodes = Self::collect_descendants(self.root);
+        // ノードは IntoIter が解放する
+        self.root = ptr::null_mut();
+        IntoIter {
+            nodes: nodes.into_iter(),
+        }
+    }
+}
+
+impl<T> Drop for ScapegoatTree<T> {
+    fn drop(&mut self) {
+        unsafe { bst::drop_tree(self.root) };
+    }
+}
+
+impl<'a, T> RangeSSet<'a, T> for ScapegoatTree<T>
+where
+    T: cmp::Ord + 'a,
+{
+    type Range = Range<'a, T>;
+
+    // O(log(n)) time
+    fn range<R>(&'a self, range: R) -> Self::Range
+    where
+        R: RangeBounds<T>,
+    {
+        // [u, end) を返す
+        let u = match range.start_bound() {
+            Bound::Included(x) => self.partition_point_node(|y| y < x),
+            Bound::Excluded(x) => self.partition_point_node(|y| y <= x),
+            Bound::Unbounded => self.first_node(),
+        };
+        let end = match range.end_bound() {
+            Bound::Included(x) => self.partition_point_node(|y| y <= x),
+            Bound::Excluded(x) => self.partition_point_node(|y| y < x),
+            Bound::Unbounded => ptr::null_mut(),
+        };
+        if !u.is_null() && !end.is_null() && unsafe { &*u }.x >= unsafe { &*end }.x {
+            // 始点が終点より後ろにあるので空
+            return Range {
+                u: end,
+                end,
+                _marker: PhantomData,
+            };
+        }
+        Range {
+            u,
+            end,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> NavigableSSet<T> for ScapegoatTree<T>
+where
+    T: cmp::Ord,
+{
+    // O(log(n)) time
+    fn find_gt(&self, x: &T) -> Option<&T> {
+        let u = self.partition_point_node(|y| y <= x);
+        unsafe { u.as_ref() }.map(|u| &u.x)
+    }
+
+    // O(log(n)) time
+    fn find_le(&self, x: &T) -> Option<&T> {
+        let u = self.find_pred_node_by(|y| y <= x);
+        unsafe { u.as_ref() }.map(|u| &u.x)
+    }
+
+    // O(log(n)) time
+    fn find_lt(&self, x: &T) -> Option<&T> {
+        let u = self.find_pred_node_by(|y| y < x);
+        unsafe { u.as_ref() }.map(|u| &u.x)
+    }
+
+    // O(log(n)) time
+    fn first(&self) -> Option<&T> {
+        let u = self.first_node();
+        unsafe { u.as_ref() }.map(|u| &u.x)
+    }
+
+    // O(log(n)) time
+    fn last(&self) -> Option<&T> {
+        let u = self.find_pred_node_by(|_| true);
+        unsafe { u.as_ref() }.map(|u| &u.x)
+    }
+}
+
+pub struct Range<'a, T> {
+    u: *mut Node<T>,
+    end: *mut Node<T>,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for Range<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.u == self.end {
+            return None;
+        }
+        let x = &unsafe { &*self.u }.x;
+        self.u = unsafe { bst::next_node(self.u) };
+        Some(x)
+    }
+}
+
+impl<K, V> ScapegoatTree<Pair<K, V>>
+where
+    K: cmp::Ord,
+{
+    // key をキーに持つノードを返す
+    // なければ NULL を返す
+    // O(log(n)) time
+    fn find_pair_node(&self, key: &K) -> *mut Node<Pair<K, V>> {
+        unsafe { bst::find_node_by(self.root, |p| p.key < *key, |p| p.key == *key) }
+    }
+}
+
+// ScapegoatTree の要素を (キー, 値) にしたもの
+pub type ScapegoatMap<K, V> = PairMap<K, V, ScapegoatTree<Pair<K, V>>>;
+
+impl<K, V> PairSet<K, V> for ScapegoatTree<Pair<K, V>>
+where
+    K: cmp::Ord,
+{
+    // O(log(n)) time
+    fn get_pair(&self, key: &K) -> Option<&Pair<K, V>> {
+        unsafe { self.find_pair_node(key).as_ref() }.map(|u| &u.x)
+    }
+
+    // O(log(n)) time
+    fn get_pair_mut(&mut self, key: &K) -> Option<&mut Pair<K, V>> {
+        unsafe { self.find_pair_node(key).as_mut() }.map(|u| &mut u.x)
+    }
+
+    // O(log(n)) time
+    fn find_pair(&self, key: &K) -> Option<&Pair<K, V>> {
+        let u = self.partition_point_node(|p| p.key < *key);
+        unsafe { u.as_ref() }.map(|u| &u.x)
+    }
+
+    // amortized O(log(n)) time
+    fn add_pair(&mut self, p: Pair<K, V>) -> &mut Pair<K, V> {
+        let u = self.add_node(p);
+        unsafe { &mut (*u).x }
+    }
+
+    // amortized O(log(n)) time
+    fn remove_pair(&mut self, key: &K) -> Option<Pair<K, V>> {
+        let u = self.find_pair_node(key);
+        if u.is_null() {
             None
         } else {
-            Some(&unsafe { &*z }.x)
+            Some(self.remove_node(u))
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::ScapegoatTree;
-    use interface::SSet;
+    use super::{ScapegoatMap, ScapegoatTree};
+    use interface::{testing, SSet};
     use rand::{rngs::SmallRng, Rng, SeedableRng};
     use std::collections::BTreeSet;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_drop() {
+        let x = Rc::new(());
+        let mut tree = ScapegoatTree::new();
+        for i in 0..100 {
+            tree.add((i, Rc::clone(&x)));
+        }
+        for i in 0..50 {
+            tree.remove(&(i, Rc::clone(&x)));
+        }
+        assert_eq!(Rc::strong_count(&x), 51);
+        let mut iter = tree.into_iter();
+        iter.next();
+        assert_eq!(Rc::strong_count(&x), 50);
+        drop(iter);
+        assert_eq!(Rc::strong_count(&x), 1);
+
+        let mut tree = ScapegoatTree::new();
+        for i in 0..100 {
+            tree.add((i, Rc::clone(&x)));
+        }
+        drop(tree);
+        assert_eq!(Rc::strong_count(&x), 1);
+    }
 
     #[test]
     fn add_same() {
@@ -409,4 +536,34 @@ mod tests {
             assert_eq!(removed_1, removed_2);
         }
     }
+
+    #[test]
+    fn test_iter() {
+        testing::test_iter(ScapegoatTree::new());
+    }
+
+    #[test]
+    fn test_range() {
+        testing::test_range(ScapegoatTree::new());
+    }
+
+    #[test]
+    fn test_rank_select() {
+        testing::test_rank_select(
+            ScapegoatTree::new(),
+            ScapegoatTree::rank,
+            ScapegoatTree::select,
+        );
+    }
+
+    #[test]
+    fn test_navigate() {
+        testing::test_navigate(ScapegoatTree::new());
+    }
+
+    #[test]
+    fn test_map() {
+        testing::test_map_random(ScapegoatMap::new());
+        testing::test_map_entry(ScapegoatMap::new());
+    }
 }
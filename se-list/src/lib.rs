@@ -0,0 +1,402 @@
+use std::{mem::MaybeUninit, ptr};
+
+use array_deque::{shift_left, shift_right};
+use interface::List;
+
+// 長さ固定の循環配列による両端キュー
+// a[j], a[(j+1)%len], ..., a[(j+n-1)%len] だけが初期化されている
+// 要素ごとに Option のタグを持たないように MaybeUninit で持つ
+struct BDeque<T> {
+    a: Box<[MaybeUninit<T>]>,
+    j: usize,
+    n: usize,
+}
+
+impl<T> BDeque<T> {
+    fn new(len: usize) -> Self {
+        Self {
+            a: (0..len).map(|_| MaybeUninit::uninit()).collect(),
+            j: 0,
+            n: 0,
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.n
+    }
+
+    fn index(&self, i: usize) -> usize {
+        (self.j + i) % self.a.len()
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        if i < self.n {
+            Some(unsafe { &*self.a[self.index(i)].as_ptr() })
+        } else {
+            None
+        }
+    }
+
+    fn set(&mut self, i: usize, x: T) -> T {
+        debug_assert!(i < self.n);
+        let k = self.index(i);
+        unsafe { ptr::replace(self.a[k].as_mut_ptr(), x) }
+    }
+
+    // O(1 + min(i, n-i)) time
+    fn add(&mut self, i: usize, x: T) {
+        debug_assert!(self.n < self.a.len());
+        let len = self.a.len();
+        let a = self.a.as_mut_ptr();
+        if i < self.n / 2 {
+            unsafe { shift_left(a, len, self.j, i) };
+            self.j = (self.j + len - 1) % len;
+        } else {
+            unsafe { shift_right(a, len, (self.j + i) % len, self.n - i) };
+        }
+        self.a[(self.j + i) % len] = MaybeUninit::new(x);
+        self.n += 1;
+    }
+
+    // O(1 + min(i, n-i)) time
+    fn remove(&mut self, i: usize) -> T {
+        debug_assert!(i < self.n);
+        let len = self.a.len();
+        let a = self.a.as_mut_ptr();
+        let x = unsafe { ptr::read(self.a[(self.j + i) % len].as_ptr()) };
+        if i < self.n / 2 {
+            unsafe { shift_right(a, len, self.j, i) };
+            self.j = (self.j + 1) % len;
+        } else {
+            unsafe { shift_left(a, len, (self.j + i + 1) % len, self.n - i - 1) };
+        }
+        self.n -= 1;
+        x
+    }
+}
+
+impl<T> Drop for BDeque<T> {
+    fn drop(&mut self) {
+        for i in 0..self.n {
+            let k = self.index(i);
+            unsafe { ptr::drop_in_place(self.a[k].as_mut_ptr()) };
+        }
+    }
+}
+
+struct Node<T> {
+    d: BDeque<T>,
+    next: *mut Node<T>,
+    prev: *mut Node<T>,
+}
+
+// 各ブロックは b+1 個まで要素を持てる
+// 最後のブロック以外は b-1 個以上 b+1 個以下の要素を持つ
+pub struct SEList<T> {
+    dummy: *mut Node<T>,
+    n: usize,
+    b: usize,
+}
+
+impl<T> SEList<T> {
+    pub fn new(b: usize) -> Self {
+        assert!(b >= 1, "expect `b` >= 1");
+        // dummy のブロックは使わないので長さ 0
+        let dummy = Box::into_raw(Box::new(Node {
+            d: BDeque::new(0),
+            next: ptr::null_mut(),
+            prev: ptr::null_mut(),
+        }));
+        unsafe {
+            (*dummy).next = dummy;
+            (*dummy).prev = dummy;
+        }
+        Self { dummy, n: 0, b }
+    }
+
+    // i 番目の要素があるブロックとそのブロック内での位置
+    // O(1 + min(i, n-i)/b) time
+    fn get_location(&self, i: usize) -> (*mut Node<T>, usize) {
+        debug_assert!(i < self.n);
+        if i < self.n / 2 {
+            let mut u = unsafe { &*self.dummy }.next;
+            let mut i = i;
+            while i >= unsafe { &*u }.d.size() {
+                i -= unsafe { &*u }.d.size();
+                u = unsafe { &*u }.next;
+            }
+            (u, i)
+        } else {
+            let mut u = self.dummy;
+            let mut idx = self.n;
+            while i < idx {
+                u = unsafe { &*u }.prev;
+                idx -= unsafe { &*u }.d.size();
+            }
+            (u, i - idx)
+        }
+    }
+
+    // w の前に空のブロックを追加する
+    // O(b) time
+    fn add_before(&mut self, w: *mut Node<T>) -> *mut Node<T> {
+        let u = Box::into_raw(Box::new(Node {
+            d: BDeque::new(self.b + 1),
+            next: w,
+            prev: unsafe { &*w }.prev,
+        }));
+        unsafe { (*(*u).prev).next = u };
+        unsafe { (*w).prev = u };
+        u
+    }
+
+    // O(b) time
+    fn remove_node(&mut self, w: *mut Node<T>) {
+        debug_assert_ne!(w, self.dummy);
+        let prev_w = unsafe { &*w }.prev;
+        let next_w = unsafe { &*w }.next;
+        unsafe { (*prev_w).next = next_w };
+        unsafe { (*next_w).prev = prev_w };
+        drop(unsafe { Box::from_raw(w) });
+    }
+
+    // u から始まる b 個のブロックがすべて満杯 (b+1 個) のとき、
+    // 新しいブロックを足して b+1 個のブロックに b 個ずつ入れ直す
+    // O(b^2) time
+    fn spread(&mut self, u: *mut Node<T>) {
+        let mut w = u;
+        for _ in 0..self.b {
+            w = unsafe { &*w }.next;
+        }
+        w = self.add_before(w);
+        while w != u {
+            let prev = unsafe { &*w }.prev;
+            let (wd, pd) = unsafe { (&mut (*w).d, &mut (*prev).d) };
+            while wd.size() < self.b {
+                let x = pd.remove(pd.size() - 1);
+                wd.add(0, x);
+            }
+            w = prev;
+        }
+    }
+
+    // u から始まる b 個のブロックがすべて b-1 個のとき、
+    // b-1 個のブロックに b 個ずつ詰めて最後のブロックを消す
+    // O(b^2) time
+    fn gather(&mut self, u: *mut Node<T>) {
+        let mut w = u;
+        for _ in 0..(self.b - 1) {
+            let next = unsafe { &*w }.next;
+            let (wd, nd) = unsafe { (&mut (*w).d, &mut (*next).d) };
+            while wd.size() < self.b {
+                let x = nd.remove(0);
+                wd.add(wd.size(), x);
+            }
+            w = next;
+        }
+        self.remove_node(w);
+    }
+
+    // O(1) time
+    fn add_last(&mut self, x: T) {
+        let mut last = unsafe { &*self.dummy }.prev;
+        if last == self.dummy || unsafe { &*last }.d.size() == self.b + 1 {
+            last = self.add_before(self.dummy);
+        }
+        let d = unsafe { &mut (*last).d };
+        d.add(d.size(), x);
+        self.n += 1;
+    }
+}
+
+impl<T> List<T> for SEList<T> {
+    fn size(&self) -> usize {
+        self.n
+    }
+
+    // O(1 + min(i, n-i)/b) time
+    fn get(&self, i: usize) -> Option<&T> {
+        if i < self.n {
+            let (u, j) = self.get_location(i);
+            unsafe { &*u }.d.get(j)
+        } else {
+            None
+        }
+    }
+
+    // O(1 + min(i, n-i)/b) time
+    fn set(&self, i: usize, x: T) -> T {
+        assert!(i < self.n, "expect `i` < SEList::size()");
+        let (u, j) = self.get_location(i);
+        unsafe { (*u).d.set(j, x) }
+    }
+
+    // amortized O(b + min(i, n-i)/b) time
+    fn add(&mut self, i: usize, x: T) {
+        assert!(i <= self.n, "expect `i` <= SEList::size()");
+        if i == self.n {
+            self.add_last(x);
+            return;
+        }
+        let (l, j) = self.get_location(i);
+        // 空きのあるブロックを b 個先まで探す
+        let mut u = l;
+        let mut r = 0;
+        while r < self.b && u != self.dummy && unsafe { &*u }.d.size() == self.b + 1 {
+            u = unsafe { &*u }.next;
+            r += 1;
+        }
+        if r == self.b {
+            self.spread(l);
+            u = l;
+        }
+        if u == self.dummy {
+            u = self.add_before(u);
+        }
+        // l と u の間のブロックの末尾の要素をひとつずつ後ろのブロックに送る
+        while u != l {
+            let prev = unsafe { &*u }.prev;
+            let (ud, pd) = unsafe { (&mut (*u).d, &mut (*prev).d) };
+            let y = pd.remove(pd.size() - 1);
+            ud.add(0, y);
+            u = prev;
+        }
+        unsafe { (*u).d.add(j, x) };
+        self.n += 1;
+    }
+
+    // amortized O(b + min(i, n-i)/b) time
+    fn remove(&mut self, i: usize) -> T {
+        assert!(i < self.n, "expect `i` < SEList::size()");
+        let (l, j) = self.get_location(i);
+        // 要素を b-1 個より多く持つブロックを b 個先まで探す
+        let mut u = l;
+        let mut r = 0;
+        while r < self.b && u != self.dummy && unsafe { &*u }.d.size() + 1 == self.b {
+            u = unsafe { &*u }.next;
+            r += 1;
+        }
+        if r == self.b {
+            self.gather(l);
+        }
+        u = l;
+        let x = unsafe { (*u).d.remove(j) };
+        // 後ろのブロックの先頭の要素を借りてきて b-1 個以上にする
+        loop {
+            let next = unsafe { &*u }.next;
+            if next == self.dummy || unsafe { &*u }.d.size() + 1 >= self.b {
+                break;
+            }
+            let (ud, nd) = unsafe { (&mut (*u).d, &mut (*next).d) };
+            let y = nd.remove(0);
+            ud.add(ud.size(), y);
+            u = next;
+        }
+        if unsafe { &*u }.d.size() == 0 {
+            self.remove_node(u);
+        }
+        self.n -= 1;
+        x
+    }
+}
+
+impl<T> Drop for SEList<T> {
+    fn drop(&mut self) {
+        let mut u = unsafe { &*self.dummy }.next;
+        while u != self.dummy {
+            let next = unsafe { &*u }.next;
+            drop(unsafe { Box::from_raw(u) });
+            u = next;
+        }
+        drop(unsafe { Box::from_raw(self.dummy) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SEList;
+    use interface::List;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+    use std::rc::Rc;
+
+    // 各ブロックの要素数
+    fn block_sizes<T>(list: &SEList<T>) -> Vec<usize> {
+        let mut sizes = Vec::new();
+        let mut u = unsafe { &*list.dummy }.next;
+        while u != list.dummy {
+            sizes.push(unsafe { &*u }.d.size());
+            u = unsafe { &*u }.next;
+        }
+        sizes
+    }
+
+    #[test]
+    fn test_set_get() {
+        let mut list = SEList::new(2);
+        list.add(0, 'b');
+        list.add(0, 'a');
+        list.add(2, 'd');
+        list.add(2, 'c');
+        // a b c d
+        assert_eq!(list.set(1, 'x'), 'b');
+        // a x c d
+        assert_eq!(list.get(0), Some(&'a'));
+        assert_eq!(list.get(1), Some(&'x'));
+        assert_eq!(list.get(2), Some(&'c'));
+        assert_eq!(list.get(3), Some(&'d'));
+        assert_eq!(list.get(4), None);
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for b in [1, 2, 3, 8] {
+            let mut v = Vec::new();
+            let mut list = SEList::new(b);
+            for _ in 0..1000 {
+                if v.is_empty() || rng.gen_bool(0.6) {
+                    let i = rng.gen_range(0..=v.len());
+                    let x = rng.gen::<u32>();
+                    v.insert(i, x);
+                    list.add(i, x);
+                } else {
+                    let i = rng.gen_range(0..v.len());
+                    assert_eq!(list.remove(i), v.remove(i));
+                }
+                assert_eq!(list.size(), v.len());
+                // 最後のブロック以外は b-1 個以上 b+1 個以下
+                let sizes = block_sizes(&list);
+                assert_eq!(sizes.iter().sum::<usize>(), v.len());
+                if let Some((last, rest)) = sizes.split_last() {
+                    assert!(1 <= *last && *last <= b + 1);
+                    assert!(rest.iter().all(|&s| b - 1 <= s && s <= b + 1));
+                }
+            }
+            for i in 0..v.len() {
+                assert_eq!(list.get(i), v.get(i));
+            }
+        }
+    }
+
+    #[test]
+    fn test_drop() {
+        // ブロックの間で要素を移しても、二重に drop したり drop し忘れたりしない
+        let mut rng = SmallRng::seed_from_u64(0);
+        let x = Rc::new(());
+        let mut list = SEList::new(3);
+        for _ in 0..1000 {
+            if list.size() == 0 || rng.gen_bool(0.6) {
+                let i = rng.gen_range(0..=list.size());
+                list.add(i, Rc::clone(&x));
+            } else {
+                let i = rng.gen_range(0..list.size());
+                list.remove(i);
+            }
+            assert_eq!(Rc::strong_count(&x), list.size() + 1);
+        }
+        list.set(0, Rc::clone(&x));
+        assert_eq!(Rc::strong_count(&x), list.size() + 1);
+        drop(list);
+        assert_eq!(Rc::strong_count(&x), 1);
+    }
+}
@@ -4,7 +4,7 @@ use std::{
     ptr,
 };
 
-use rand::{rngs::SmallRng, Rng, SeedableRng};
+use rand::{rngs::SmallRng, Rng, RngCore, SeedableRng};
 
 use interface::List;
 
@@ -24,29 +24,52 @@ impl<T> Node<T> {
     }
 }
 
-pub struct SkipListList<T> {
+pub struct SkipListList<T, R = SmallRng> {
     sentinel: *mut Node<T>,
     height: usize,
     n: usize,
+    rng: R,
 }
 
 impl<T> SkipListList<T> {
+    // seed はインスタンスごとに OS の乱数から決める
     pub fn new() -> Self {
+        Self::with_rng(SmallRng::from_entropy())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(SmallRng::seed_from_u64(seed))
+    }
+}
+
+impl<T> Default for SkipListList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, R> SkipListList<T, R>
+where
+    R: RngCore,
+{
+    pub fn with_rng(rng: R) -> Self {
         let sentinel = Node::new(None, 32);
         let sentinel = Box::into_raw(Box::new(sentinel));
         Self {
             sentinel,
             height: 0,
             n: 0,
+            rng,
         }
     }
 
     // copy of SkipListSSet::pick_height
-    fn pick_height() -> usize {
-        let mut small_rng = SmallRng::from_entropy();
-        small_rng.gen_range(0..u32::MAX).trailing_ones() as usize
+    fn pick_height(&mut self) -> usize {
+        self.rng.gen_range(0..u32::MAX).trailing_ones() as usize
     }
+}
 
+impl<T, R> SkipListList<T, R> {
     fn find_pred(&self, i: usize) -> *mut Node<T> {
         let mut u = self.sentinel;
         let mut u_index = 0_usize.wrapping_sub(1);
@@ -69,7 +92,10 @@ impl<T> SkipListList<T> {
     }
 }
 
-impl<T> List<T> for SkipListList<T> {
+impl<T, R> List<T> for SkipListList<T, R>
+where
+    R: RngCore,
+{
     fn size(&self) -> usize {
         self.n
     }
@@ -96,7 +122,7 @@ impl<T> List<T> for SkipListList<T> {
 
     fn add(&mut self, i: usize, x: T) {
         assert!(i <= self.size());
-        let w_height = Self::pick_height();
+        let w_height = self.pick_height();
         let w = Box::into_raw(Box::new(Node::new(Some(x), w_height)));
         self.height = self.height.max(w_height);
         let mut u = self.sentinel;
@@ -188,7 +214,7 @@ impl<T> List<T> for SkipListList<T> {
     }
 }
 
-impl<T> fmt::Debug for SkipListList<T>
+impl<T, R> fmt::Debug for SkipListList<T, R>
 where
     T: fmt::Debug,
 {
@@ -220,11 +246,12 @@ where
 mod test {
     use super::SkipListList;
     use interface::List;
-    use rand::{rngs::SmallRng, Rng, SeedableRng};
+    use rand::rngs::{mock::StepRng, SmallRng};
+    use rand::{Rng, SeedableRng};
 
     #[test]
     fn test_get_none() {
-        let mut list = SkipListList::new();
+        let mut list = SkipListList::with_seed(0);
 
         let nil = list.get(0);
         assert_eq!(nil, None);
@@ -236,7 +263,7 @@ mod test {
 
     #[test]
     fn test_set_get() {
-        let mut list = SkipListList::new();
+        let mut list = SkipListList::with_seed(0);
         list.add(0, 'a');
         list.add(1, 'b');
         list.add(2, 'c');
@@ -254,7 +281,7 @@ mod test {
 
     #[test]
     fn test_add_remove() {
-        let mut list = SkipListList::new();
+        let mut list = SkipListList::with_seed(0);
         list.add(0, 'a');
         list.add(1, 'b');
         list.add(2, 'c');
@@ -278,7 +305,7 @@ mod test {
     fn test_random() {
         let mut rng = SmallRng::seed_from_u64(0);
         let mut v = Vec::new();
-        let mut list = SkipListList::new();
+        let mut list = SkipListList::with_seed(0);
         for i in 0..100 {
             let i = rng.gen_range(0..=i);
             v.insert(i, i);
@@ -291,4 +318,46 @@ mod test {
             assert_eq!(x, y);
         }
     }
+
+    #[test]
+    fn test_with_seed() {
+        let mut a = SkipListList::with_seed(1);
+        let mut b = SkipListList::with_seed(1);
+        for i in 0..100 {
+            a.add(i, i);
+            b.add(i, i);
+        }
+        assert_eq!(a.height, b.height);
+        for i in 0..100 {
+            assert_eq!(a.get(i), Some(&i));
+        }
+    }
+
+    #[test]
+    fn test_with_rng() {
+        // 高さが常に 0 になる
+        let mut list = SkipListList::with_rng(StepRng::new(0, 0));
+        for i in 0..10 {
+            list.add(i, i);
+        }
+        assert_eq!(list.height, 0);
+        for i in 0..10 {
+            assert_eq!(list.get(i), Some(&i));
+        }
+    }
+
+    #[test]
+    fn test_new() {
+        // seed は OS の乱数から決めるので、結果が seed によらないことだけ確かめる
+        let mut list = SkipListList::new();
+        let mut other = SkipListList::default();
+        for i in 0..100 {
+            list.add(i, i);
+            other.add(i, i);
+        }
+        for i in 0..100 {
+            assert_eq!(list.get(i), Some(&i));
+            assert_eq!(other.get(i), Some(&i));
+        }
+    }
 }
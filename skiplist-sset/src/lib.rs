@@ -1,12 +1,13 @@
 use std::{
-    alloc,
     fmt::{self, Formatter},
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
     ptr,
 };
 
-use rand::{rngs::SmallRng, Rng, SeedableRng};
+use rand::{rngs::SmallRng, Rng, RngCore, SeedableRng};
 
-use interface::SSet;
+use interface::{NavigableSSet, Pair, PairMap, PairSet, RangeSSet, SSet};
 
 struct Node<T>
 where
@@ -32,74 +33,71 @@ where
     }
 }
 
-pub struct SkipListSSet<T>
+pub struct SkipListSSet<T, R = SmallRng>
 where
     T: PartialOrd,
 {
     sentinel: *mut Node<T>,
     height: usize, // height != 0 のとき「i <= height iff. sentinel.next[i] が non null」となるようにする
     n: usize,
+    rng: R,
 }
 
 impl<T> SkipListSSet<T>
 where
     T: PartialOrd,
 {
+    // seed はインスタンスごとに OS の乱数から決める
     pub fn new() -> Self {
+        Self::with_rng(SmallRng::from_entropy())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(SmallRng::seed_from_u64(seed))
+    }
+}
+
+impl<T> Default for SkipListSSet<T>
+where
+    T: PartialOrd,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, R> SkipListSSet<T, R>
+where
+    T: PartialOrd,
+    R: RngCore,
+{
+    pub fn with_rng(rng: R) -> Self {
         let sentinel = Node::new(None, 32);
         let sentinel = Box::into_raw(Box::new(sentinel));
         Self {
             sentinel,
             height: 0,
             n: 0,
+            rng,
         }
     }
 
-    fn pick_height() -> usize {
-        // 毎回 rng を生成しているためパフォーマンスが悪そう
-        let mut small_rng = SmallRng::from_entropy();
+    fn pick_height(&mut self) -> usize {
         // 返り値 : 確率
         // 0 : 1/2
         // 1 : 1/4
         // 2 : 1/8
         // 3 : 1/16
         // ...
-        small_rng.gen_range(0..u32::MAX).trailing_ones() as usize
-    }
-
-    // expected O(log(n)) time
-    fn find_pred_node(&self, x: &T) -> *mut Node<T> {
-        let mut u = self.sentinel;
-        for r in (0..=self.height).rev() {
-            loop {
-                let next = unsafe { &*u }.next[r];
-                if next.is_null() {
-                    break;
-                }
-                let y = unsafe { &*next }.x.as_ref().unwrap();
-                if y.lt(x) {
-                    u = next;
-                } else {
-                    break;
-                }
-            }
-        }
-        u
-    }
-}
-
-impl<T> SSet<T> for SkipListSSet<T>
-where
-    T: PartialOrd,
-{
-    fn size(&self) -> usize {
-        self.n
+        self.rng.gen_range(0..u32::MAX).trailing_ones() as usize
     }
 
+    // x を追加して、x を持つノードを返す
+    // x に等しい要素がすでにあるときは何もせずに NULL を返す
     // expected O(log(n)) time
-    fn add(&mut self, x: T) -> bool {
+    fn add_node(&mut self, x: T) -> *mut Node<T> {
         let mut u = self.sentinel;
-        let h = Self::pick_height(); // 新しく追加するノードの高さ
+        let h = self.pick_height(); // 新しく追加するノードの高さ
         let mut stack = Vec::new(); // 固定長の配列 self.buf: [*mut Node<T>; 32] を使い回すほうが速くなりそう
         for r in (0..=self.height.max(h)).rev() {
             let exist = loop {
@@ -117,7 +115,7 @@ where
             };
             if exist {
                 // x と等しい要素があった場合ノードを追加しない
-                return false;
+                return ptr::null_mut();
             }
             stack.push(u);
         }
@@ -136,11 +134,17 @@ where
         self.height = self.height.max(h);
         self.n += 1;
 
-        true
+        w
     }
 
+    // lt(y) = true となる要素の次にある、eq(y) = true となる要素を取り除いて返す
+    // lt, eq はそれぞれ y < x, y == x に相当する
     // expected O(log(n)) time
-    fn remove(&mut self, x: &T) -> bool {
+    fn remove_by<F, G>(&mut self, lt: F, eq: G) -> Option<T>
+    where
+        F: Fn(&T) -> bool,
+        G: Fn(&T) -> bool,
+    {
         let mut removed = false;
         let mut del = ptr::null_mut();
         let mut u = self.sentinel;
@@ -152,10 +156,10 @@ where
                     break false;
                 }
                 let y = unsafe { &*next }.x.as_ref().unwrap();
-                if x.gt(y) {
+                if lt(y) {
                     u = next;
                 } else {
-                    break x.eq(y);
+                    break eq(y);
                 }
             };
             if delete_next_node {
@@ -175,11 +179,75 @@ where
         }
         if removed {
             debug_assert!(!del.is_null());
-            unsafe { ptr::drop_in_place(del) };
-            unsafe { alloc::dealloc(del as *mut u8, alloc::Layout::new::<Node<T>>()) };
             self.n -= 1;
+            let del = unsafe { Box::from_raw(del) };
+            del.x
+        } else {
+            None
+        }
+    }
+}
+
+impl<T, R> SkipListSSet<T, R>
+where
+    T: PartialOrd,
+{
+    // expected O(log(n)) time
+    fn find_pred_node(&self, x: &T) -> *mut Node<T> {
+        self.find_pred_node_by(|y| y.lt(x))
+    }
+
+    // pred(y) = true となる要素をもつノードのうち最後のものを返す
+    // そのようなノードがなければ sentinel を返す
+    // pred は小さい要素で true, 大きい要素で false になっている必要がある
+    // expected O(log(n)) time
+    fn find_pred_node_by<F>(&self, pred: F) -> *mut Node<T>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let mut u = self.sentinel;
+        for r in (0..=self.height).rev() {
+            loop {
+                let next = unsafe { &*u }.next[r];
+                if next.is_null() {
+                    break;
+                }
+                let y = unsafe { &*next }.x.as_ref().unwrap();
+                if pred(y) {
+                    u = next;
+                } else {
+                    break;
+                }
+            }
+        }
+        u
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            u: unsafe { &*self.sentinel }.next[0],
+            _marker: PhantomData,
         }
-        removed
+    }
+}
+
+impl<T, R> SSet<T> for SkipListSSet<T, R>
+where
+    T: PartialOrd,
+    R: RngCore,
+{
+    fn size(&self) -> usize {
+        self.n
+    }
+
+    // expected O(log(n)) time
+    fn add(&mut self, x: T) -> bool {
+        !self.add_node(x).is_null()
+    }
+
+    // expected O(log(n)) time
+    fn remove(&mut self, x: &T) -> bool {
+        self.remove_by(|y| y.lt(x), |y| y.eq(x)).is_some()
     }
 
     // expected O(log(n)) time
@@ -196,7 +264,209 @@ where
     }
 }
 
-impl<T> fmt::Debug for SkipListSSet<T>
+pub struct Iter<'a, T>
+where
+    T: PartialOrd,
+{
+    u: *mut Node<T>,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T>
+where
+    T: PartialOrd,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.u.is_null() {
+            return None;
+        }
+        let u = unsafe { &*self.u };
+        self.u = u.next[0];
+        let x = u.x.as_ref();
+        debug_assert!(x.is_some());
+        x
+    }
+}
+
+impl<'a, T, R> IntoIterator for &'a SkipListSSet<T, R>
+where
+    T: PartialOrd,
+{
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct IntoIter<T>
+where
+    T: PartialOrd,
+{
+    u: *mut Node<T>,
+}
+
+impl<T> Iterator for IntoIter<T>
+where
+    T: PartialOrd,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.u.is_null() {
+            return None;
+        }
+        // 返したノードはもう辿らないのでその場で解放する
+        let u = unsafe { Box::from_raw(self.u) };
+        self.u = u.next[0];
+        debug_assert!(u.x.is_some());
+        u.x
+    }
+}
+
+impl<T> Drop for IntoIter<T>
+where
+    T: PartialOrd,
+{
+    fn drop(&mut self) {
+        for _ in self {}
+    }
+}
+
+impl<T, R> IntoIterator for SkipListSSet<T, R>
+where
+    T: PartialOrd,
+{
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let sentinel = unsafe { Box::from_raw(self.sentinel) };
+        IntoIter { u: sentinel.next[0] }
+    }
+}
+
+impl<'a, T, R> RangeSSet<'a, T> for SkipListSSet<T, R>
+where
+    T: PartialOrd + 'a,
+    R: RngCore,
+{
+    type Range = Range<'a, T>;
+
+    // expected O(log(n)) time
+    fn range<B>(&'a self, range: B) -> Self::Range
+    where
+        B: RangeBounds<T>,
+    {
+        // [u, end) を返す
+        let u = match range.start_bound() {
+            Bound::Included(x) => self.find_pred_node_by(|y| y.lt(x)),
+            Bound::Excluded(x) => self.find_pred_node_by(|y| y.le(x)),
+            Bound::Unbounded => self.sentinel,
+        };
+        let u = unsafe { &*u }.next[0];
+        let end = match range.end_bound() {
+            Bound::Included(x) => unsafe { &*self.find_pred_node_by(|y| y.le(x)) }.next[0],
+            Bound::Excluded(x) => unsafe { &*self.find_pred_node_by(|y| y.lt(x)) }.next[0],
+            Bound::Unbounded => ptr::null_mut(),
+        };
+        if !u.is_null() && !end.is_null() {
+            let x = unsafe { &*u }.x.as_ref().unwrap();
+            let y = unsafe { &*end }.x.as_ref().unwrap();
+            if !x.lt(y) {
+                // 始点が終点より後ろにあるので空
+                return Range {
+                    u: end,
+                    end,
+                    _marker: PhantomData,
+                };
+            }
+        }
+        Range {
+            u,
+            end,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, R> NavigableSSet<T> for SkipListSSet<T, R>
+where
+    T: PartialOrd,
+    R: RngCore,
+{
+    // expected O(log(n)) time
+    fn find_gt(&self, x: &T) -> Option<&T> {
+        let u = self.find_pred_node_by(|y| y.le(x));
+        let next = unsafe { &*u }.next[0];
+        if next.is_null() {
+            None
+        } else {
+            unsafe { &*next }.x.as_ref()
+        }
+    }
+
+    // expected O(log(n)) time
+    fn find_le(&self, x: &T) -> Option<&T> {
+        let u = self.find_pred_node_by(|y| y.le(x));
+        // u = sentinel なら None
+        unsafe { &*u }.x.as_ref()
+    }
+
+    // expected O(log(n)) time
+    fn find_lt(&self, x: &T) -> Option<&T> {
+        let u = self.find_pred_node(x);
+        unsafe { &*u }.x.as_ref()
+    }
+
+    // O(1) time
+    fn first(&self) -> Option<&T> {
+        let u = unsafe { &*self.sentinel }.next[0];
+        if u.is_null() {
+            None
+        } else {
+            unsafe { &*u }.x.as_ref()
+        }
+    }
+
+    // expected O(log(n)) time
+    fn last(&self) -> Option<&T> {
+        let u = self.find_pred_node_by(|_| true);
+        unsafe { &*u }.x.as_ref()
+    }
+}
+
+pub struct Range<'a, T>
+where
+    T: PartialOrd,
+{
+    u: *mut Node<T>,
+    end: *mut Node<T>,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for Range<'a, T>
+where
+    T: PartialOrd,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.u == self.end {
+            return None;
+        }
+        let u = unsafe { &*self.u };
+        self.u = u.next[0];
+        let x = u.x.as_ref();
+        debug_assert!(x.is_some());
+        x
+    }
+}
+
+impl<T, R> fmt::Debug for SkipListSSet<T, R>
 where
     T: PartialOrd + fmt::Debug,
 {
@@ -221,14 +491,75 @@ where
     }
 }
 
+impl<K, V, R> SkipListSSet<Pair<K, V>, R>
+where
+    K: PartialOrd,
+{
+    // key 以上の最小のキーを持つノードを返す
+    // なければ NULL を返す
+    // expected O(log(n)) time
+    fn find_pair_node(&self, key: &K) -> *mut Node<Pair<K, V>> {
+        let u = self.find_pred_node_by(|p| p.key.lt(key));
+        unsafe { &*u }.next[0]
+    }
+
+    // key をキーに持つノードを返す
+    // なければ NULL を返す
+    // expected O(log(n)) time
+    fn find_pair_node_eq(&self, key: &K) -> *mut Node<Pair<K, V>> {
+        let u = self.find_pair_node(key);
+        if !u.is_null() && unsafe { &*u }.x.as_ref().unwrap().key.eq(key) {
+            u
+        } else {
+            ptr::null_mut()
+        }
+    }
+}
+
+// SkipListSSet の要素を (キー, 値) にしたもの
+pub type SkipListMap<K, V, R = SmallRng> = PairMap<K, V, SkipListSSet<Pair<K, V>, R>>;
+
+impl<K, V, R> PairSet<K, V> for SkipListSSet<Pair<K, V>, R>
+where
+    K: PartialOrd,
+    R: RngCore,
+{
+    // expected O(log(n)) time
+    fn get_pair(&self, key: &K) -> Option<&Pair<K, V>> {
+        unsafe { self.find_pair_node_eq(key).as_ref() }.and_then(|u| u.x.as_ref())
+    }
+
+    // expected O(log(n)) time
+    fn get_pair_mut(&mut self, key: &K) -> Option<&mut Pair<K, V>> {
+        unsafe { self.find_pair_node_eq(key).as_mut() }.and_then(|u| u.x.as_mut())
+    }
+
+    // expected O(log(n)) time
+    fn find_pair(&self, key: &K) -> Option<&Pair<K, V>> {
+        unsafe { self.find_pair_node(key).as_ref() }.and_then(|u| u.x.as_ref())
+    }
+
+    // expected O(log(n)) time
+    fn add_pair(&mut self, p: Pair<K, V>) -> &mut Pair<K, V> {
+        let u = self.add_node(p);
+        unsafe { (*u).x.as_mut() }.unwrap()
+    }
+
+    // expected O(log(n)) time
+    fn remove_pair(&mut self, key: &K) -> Option<Pair<K, V>> {
+        self.remove_by(|p| p.key.lt(key), |p| p.key.eq(key))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::SkipListSSet;
-    use interface::SSet;
+    use super::{SkipListMap, SkipListSSet};
+    use interface::{testing, SSet};
+    use rand::rngs::mock::StepRng;
 
     #[test]
     fn test_add_twice() {
-        let mut set = SkipListSSet::new();
+        let mut set = SkipListSSet::with_seed(0);
         let added = set.add('a');
         assert!(added);
         assert_eq!(set.size(), 1);
@@ -238,8 +569,44 @@ mod tests {
     }
 
     #[test]
-    fn test_remove_twice() {
+    fn test_with_seed() {
+        let mut set_1 = SkipListSSet::with_seed(42);
+        let mut set_2 = SkipListSSet::with_seed(42);
+        for x in 0..100 {
+            set_1.add(x);
+            set_2.add(x);
+        }
+        assert_eq!(set_1.height, set_2.height);
+        assert!(set_1.iter().eq(set_2.iter()));
+    }
+
+    #[test]
+    fn test_with_rng() {
+        // 高さが常に 0 になる
+        let mut set = SkipListSSet::with_rng(StepRng::new(0, 0));
+        for x in 0..10 {
+            set.add(x);
+        }
+        assert_eq!(set.height, 0);
+        assert!(set.iter().eq((0..10).collect::<Vec<_>>().iter()));
+    }
+
+    #[test]
+    fn test_new() {
+        // seed は OS の乱数から決めるので、結果が seed によらないことだけ確かめる
         let mut set = SkipListSSet::new();
+        let mut other = SkipListSSet::default();
+        for x in 0..100 {
+            set.add(x);
+            other.add(x);
+        }
+        assert!(set.iter().eq(other.iter()));
+        assert!(set.iter().copied().eq(0..100));
+    }
+
+    #[test]
+    fn test_remove_twice() {
+        let mut set = SkipListSSet::with_seed(0);
         set.add('a');
         let removed = set.remove(&'a');
         assert!(removed);
@@ -249,7 +616,7 @@ mod tests {
 
     #[test]
     fn test_find() {
-        let mut set = SkipListSSet::new();
+        let mut set = SkipListSSet::with_seed(0);
         assert_eq!(set.find(&'a'), None);
         set.add('a');
         set.add('p');
@@ -268,4 +635,25 @@ mod tests {
         assert_eq!(set.find(&'q'), Some(&'q'));
         assert_eq!(set.find(&'r'), None);
     }
+
+    #[test]
+    fn test_iter() {
+        testing::test_iter(SkipListSSet::with_seed(0));
+    }
+
+    #[test]
+    fn test_range() {
+        testing::test_range(SkipListSSet::with_seed(0));
+    }
+
+    #[test]
+    fn test_navigate() {
+        testing::test_navigate(SkipListSSet::with_seed(0));
+    }
+
+    #[test]
+    fn test_map() {
+        testing::test_map_random(SkipListMap::with_set(SkipListSSet::with_seed(0)));
+        testing::test_map_entry(SkipListMap::with_set(SkipListSSet::with_seed(0)));
+    }
 }
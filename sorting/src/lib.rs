@@ -0,0 +1,818 @@
+use rand::{rngs::SmallRng, Rng, RngCore, SeedableRng};
+use std::{cmp::Ordering, marker::PhantomData, mem, ptr};
+
+use binary_trie::IntValue;
+use interface::List;
+
+// 以下のソートは compare(x, y) の順に並べて、compare を呼んだ回数を返す
+
+// a[i] と a[j] の比較で添字を並べ替えてから、その順に a を並べ替える
+// T: Clone を要求せずに済む
+// O(n log(n)) time
+pub fn merge_sort<T, C>(a: &mut [T], mut compare: C) -> usize
+where
+    C: FnMut(&T, &T) -> Ordering,
+{
+    let mut count = 0;
+    let mut cmp = |i: &usize, j: &usize| {
+        count += 1;
+        compare(&a[*i], &a[*j])
+    };
+    let mut p: Vec<usize> = (0..a.len()).collect();
+    let mut buf = Vec::with_capacity(a.len());
+    merge_sort_rec(&mut p, &mut buf, &mut cmp);
+    apply_permutation(a, &p);
+    count
+}
+
+fn merge_sort_rec<C>(p: &mut [usize], buf: &mut Vec<usize>, cmp: &mut C)
+where
+    C: FnMut(&usize, &usize) -> Ordering,
+{
+    let n = p.len();
+    if n <= 1 {
+        return;
+    }
+    let (p0, p1) = p.split_at_mut(n / 2);
+    merge_sort_rec(p0, buf, cmp);
+    merge_sort_rec(p1, buf, cmp);
+    buf.clear();
+    let mut i0 = 0;
+    let mut i1 = 0;
+    while i0 < p0.len() && i1 < p1.len() {
+        // 等しいときは前半を先にして安定にする
+        if cmp(&p0[i0], &p1[i1]) == Ordering::Greater {
+            buf.push(p1[i1]);
+            i1 += 1;
+        } else {
+            buf.push(p0[i0]);
+            i0 += 1;
+        }
+    }
+    buf.extend_from_slice(&p0[i0..]);
+    buf.extend_from_slice(&p1[i1..]);
+    p.copy_from_slice(buf);
+}
+
+// 並べ替えたあとの a[k] が元の a[p[k]] になるようにする
+// p は 0..n の順列で、途中で compare などを呼ばないので要素をそのままコピーして移してよい
+// O(n) time
+fn apply_permutation<T>(a: &mut [T], p: &[usize]) {
+    assert_eq!(a.len(), p.len());
+    let mut b: Vec<T> = Vec::with_capacity(a.len());
+    unsafe {
+        for (k, &i) in p.iter().enumerate() {
+            ptr::copy_nonoverlapping(a.as_ptr().add(i), b.as_mut_ptr().add(k), 1);
+        }
+        // b の長さは 0 のままなので、b の drop で要素は解放されない
+        ptr::copy_nonoverlapping(b.as_ptr(), a.as_mut_ptr(), a.len());
+    }
+}
+
+// seed は OS の乱数から決める
+// expected O(n log(n)) time
+pub fn quick_sort<T, C>(a: &mut [T], compare: C) -> usize
+where
+    C: FnMut(&T, &T) -> Ordering,
+{
+    quick_sort_with_rng(a, compare, &mut SmallRng::from_entropy())
+}
+
+// pivot を rng で選ぶ
+// expected O(n log(n)) time
+pub fn quick_sort_with_rng<T, C, R>(a: &mut [T], mut compare: C, rng: &mut R) -> usize
+where
+    C: FnMut(&T, &T) -> Ordering,
+    R: RngCore,
+{
+    let mut count = 0;
+    let mut cmp = |x: &T, y: &T| {
+        count += 1;
+        compare(x, y)
+    };
+    quick_sort_rec(a, &mut cmp, rng);
+    count
+}
+
+fn quick_sort_rec<T, C, R>(mut a: &mut [T], cmp: &mut C, rng: &mut R)
+where
+    C: FnMut(&T, &T) -> Ordering,
+    R: RngCore,
+{
+    // 短いほうを再帰で、長いほうをループで処理して再帰を O(log(n)) 段に抑える
+    while a.len() > 1 {
+        let n = a.len();
+        // pivot を a[0] に置いたまま a[1..] を (< pivot, = pivot, > pivot) に分ける
+        a.swap(0, rng.gen_range(0..n));
+        // a[1..=p] < pivot, a[p+1..j] = pivot, a[q..] > pivot
+        let mut p = 0;
+        let mut j = 1;
+        let mut q = n;
+        while j < q {
+            match cmp(&a[j], &a[0]) {
+                Ordering::Less => {
+                    p += 1;
+                    a.swap(j, p);
+                    j += 1;
+                }
+                Ordering::Greater => {
+                    q -= 1;
+                    a.swap(j, q);
+                }
+                Ordering::Equal => {
+                    j += 1;
+                }
+            }
+        }
+        a.swap(0, p);
+        let (left, rest) = a.split_at_mut(p);
+        let right = &mut rest[q - p..];
+        if left.len() < right.len() {
+            quick_sort_rec(left, cmp, rng);
+            a = right;
+        } else {
+            quick_sort_rec(right, cmp, rng);
+            a = left;
+        }
+    }
+}
+
+// 最大値を根に持つヒープを作り、根を末尾と入れ替えていく
+// O(n log(n)) time
+pub fn heap_sort<T, C>(a: &mut [T], mut compare: C) -> usize
+where
+    C: FnMut(&T, &T) -> Ordering,
+{
+    let mut count = 0;
+    let mut cmp = |x: &T, y: &T| {
+        count += 1;
+        compare(x, y)
+    };
+    let n = a.len();
+    for i in (0..n / 2).rev() {
+        trickle_down(a, i, n, &mut cmp);
+    }
+    for m in (1..n).rev() {
+        a.swap(0, m);
+        trickle_down(a, 0, m, &mut cmp);
+    }
+    count
+}
+
+// a[..n] をヒープとして a[i] を下ろす
+// O(log(n)) time
+fn trickle_down<T, C>(a: &mut [T], mut i: usize, n: usize, cmp: &mut C)
+where
+    C: FnMut(&T, &T) -> Ordering,
+{
+    loop {
+        let l = i * 2 + 1;
+        let r = i * 2 + 2;
+        let mut j = i;
+        if l < n && cmp(&a[l], &a[j]) == Ordering::Greater {
+            j = l;
+        }
+        if r < n && cmp(&a[r], &a[j]) == Ordering::Greater {
+            j = r;
+        }
+        if j == i {
+            break;
+        }
+        a.swap(i, j);
+        i = j;
+    }
+}
+
+// 以下の 3 つは List の上で並べ替える
+// merge sort と quick sort は末尾の追加・削除だけを使い、途中の要素を Default で作った List に移す
+// 比較が panic しても要素は list に戻す (順序は決まらない)
+
+// 末尾の追加・削除が O(1) の List (ArrayStack, DLList など) なら O(n log(n)) time
+pub fn merge_sort_list<T, L, C>(list: &mut L, mut compare: C) -> usize
+where
+    L: List<T> + Default,
+    C: FnMut(&T, &T) -> Ordering,
+{
+    let mut count = 0;
+    let mut cmp = |x: &T, y: &T| {
+        count += 1;
+        compare(x, y)
+    };
+    merge_sort_list_rec(list, false, false, &mut cmp);
+    count
+}
+
+// desc なら降順、そうでなければ昇順に安定に並べる
+// rev のとき list は元の順序の逆に並んでいる
+fn merge_sort_list_rec<T, L, C>(list: &mut L, desc: bool, rev: bool, cmp: &mut C)
+where
+    L: List<T> + Default,
+    C: FnMut(&T, &T) -> Ordering,
+{
+    let n = list.size();
+    if n <= 1 {
+        return;
+    }
+    let mut g = Parts::new(list, 2);
+    let Parts { list, parts, .. } = &mut g;
+    let (b, out) = parts.split_at_mut(1);
+    let (b, out) = (&mut b[0], &mut out[0]);
+    // 後半を末尾から b に移すので b は逆順になる
+    for _ in n / 2..n {
+        let x = pop(*list);
+        push(b, x);
+    }
+    // 末尾から並べたい順に取り出せるように、逆向きに並べておく
+    merge_sort_list_rec(*list, !desc, rev, cmp);
+    merge_sort_list_rec(b, !desc, !rev, cmp);
+    while list.size() > 0 && b.size() > 0 {
+        let x = list.get(list.size() - 1).unwrap();
+        let y = b.get(b.size() - 1).unwrap();
+        // 等しいときは元の位置が前のほうを先にする
+        let mut o = cmp(x, y).then(if rev {
+            Ordering::Greater
+        } else {
+            Ordering::Less
+        });
+        if desc {
+            o = o.reverse();
+        }
+        let x = if o == Ordering::Less {
+            pop(*list)
+        } else {
+            pop(b)
+        };
+        push(out, x);
+    }
+    while list.size() > 0 {
+        let x = pop(*list);
+        push(out, x);
+    }
+    while b.size() > 0 {
+        let x = pop(b);
+        push(out, x);
+    }
+    mem::swap(*list, out);
+}
+
+// seed は OS の乱数から決める
+// pivot を取り除くのに O(n) かかる List でも expected O(n log(n)) time
+pub fn quick_sort_list<T, L, C>(list: &mut L, compare: C) -> usize
+where
+    L: List<T> + Default,
+    C: FnMut(&T, &T) -> Ordering,
+{
+    quick_sort_list_with_rng(list, compare, &mut SmallRng::from_entropy())
+}
+
+// pivot を rng で選ぶ
+// expected O(n log(n)) time
+pub fn quick_sort_list_with_rng<T, L, C, R>(list: &mut L, mut compare: C, rng: &mut R) -> usize
+where
+    L: List<T> + Default,
+    C: FnMut(&T, &T) -> Ordering,
+    R: RngCore,
+{
+    let mut count = 0;
+    let mut cmp = |x: &T, y: &T| {
+        count += 1;
+        compare(x, y)
+    };
+    quick_sort_list_rec(list, false, &mut cmp, rng);
+    count
+}
+
+// desc なら降順、そうでなければ昇順に並べる
+// 再帰は expected O(log(n)) 段
+fn quick_sort_list_rec<T, L, C, R>(list: &mut L, desc: bool, cmp: &mut C, rng: &mut R)
+where
+    L: List<T> + Default,
+    C: FnMut(&T, &T) -> Ordering,
+    R: RngCore,
+{
+    let n = list.size();
+    if n <= 1 {
+        return;
+    }
+    let mut g = Parts::new(list, 3);
+    let Parts { list, parts, .. } = &mut g;
+    // parts を (< pivot, = pivot, > pivot) に分ける
+    // pivot は parts[1] の先頭に置いて比べる
+    let pivot = list.remove(rng.gen_range(0..n));
+    push(&mut parts[1], pivot);
+    while list.size() > 0 {
+        let o = cmp(list.get(list.size() - 1).unwrap(), parts[1].get(0).unwrap());
+        let x = pop(*list);
+        match o {
+            Ordering::Less => push(&mut parts[0], x),
+            Ordering::Equal => push(&mut parts[1], x),
+            Ordering::Greater => push(&mut parts[2], x),
+        }
+    }
+    // 末尾から並べたい順に取り出せるように、逆向きに並べておく
+    quick_sort_list_rec(&mut parts[0], !desc, cmp, rng);
+    quick_sort_list_rec(&mut parts[2], !desc, cmp, rng);
+    if desc {
+        parts.reverse();
+    }
+    for part in parts.iter_mut() {
+        while part.size() > 0 {
+            let x = pop(part);
+            push(*list, x);
+        }
+    }
+}
+
+// get, set で要素を入れ替えながら heap sort する
+// get, set を O(n log(n)) 回呼ぶので、get, set が O(1) の List (ArrayStack など) なら O(n log(n)) time
+pub fn heap_sort_list<T, L, C>(list: &mut L, mut compare: C) -> usize
+where
+    L: List<T>,
+    C: FnMut(&T, &T) -> Ordering,
+{
+    let mut count = 0;
+    let mut cmp = |x: &T, y: &T| {
+        count += 1;
+        compare(x, y)
+    };
+    let n = list.size();
+    for i in (0..n / 2).rev() {
+        trickle_down_list(list, i, n, &mut cmp);
+    }
+    for m in (1..n).rev() {
+        swap_list(list, 0, m);
+        trickle_down_list(list, 0, m, &mut cmp);
+    }
+    count
+}
+
+// trickle_down の List 版
+// O(log(n)) 回の get, set
+fn trickle_down_list<T, L, C>(list: &L, mut i: usize, n: usize, cmp: &mut C)
+where
+    L: List<T>,
+    C: FnMut(&T, &T) -> Ordering,
+{
+    let get = |i: usize| list.get(i).unwrap();
+    loop {
+        let l = i * 2 + 1;
+        let r = i * 2 + 2;
+        let mut j = i;
+        if l < n && cmp(get(l), get(j)) == Ordering::Greater {
+            j = l;
+        }
+        if r < n && cmp(get(r), get(j)) == Ordering::Greater {
+            j = r;
+        }
+        if j == i {
+            break;
+        }
+        swap_list(list, i, j);
+        i = j;
+    }
+}
+
+// T: Clone を要求しないように、i 番目の要素をビットごと読み出して j 番目に set する
+// 取り出した元の j 番目を i 番目に set し、i 番目に残っていた同じ要素は drop せずに捨てる
+// 2 回の get, set
+fn swap_list<T, L: List<T>>(list: &L, i: usize, j: usize) {
+    if i == j {
+        return;
+    }
+    let x = unsafe { ptr::read(list.get(i).unwrap()) };
+    let y = list.set(j, x);
+    mem::forget(list.set(i, y));
+}
+
+fn pop<T, L: List<T>>(list: &mut L) -> T {
+    list.remove(list.size() - 1)
+}
+
+fn push<T, L: List<T>>(list: &mut L, x: T) {
+    list.add(list.size(), x);
+}
+
+// 並べ替えの途中で要素を移しておく List
+// drop のときに残っている要素を list に戻すので、比較が panic しても要素は失われない
+struct Parts<'a, T, L: List<T>> {
+    list: &'a mut L,
+    parts: Vec<L>,
+    marker: PhantomData<T>,
+}
+
+impl<'a, T, L: List<T> + Default> Parts<'a, T, L> {
+    fn new(list: &'a mut L, k: usize) -> Self {
+        Self {
+            list,
+            parts: (0..k).map(|_| L::default()).collect(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, L: List<T>> Drop for Parts<'a, T, L> {
+    fn drop(&mut self) {
+        for part in self.parts.iter_mut() {
+            while part.size() > 0 {
+                let x = pop(part);
+                push(self.list, x);
+            }
+        }
+    }
+}
+
+// 以下のソートは比較をせずに整数値の昇順に並べる
+// どちらも安定
+// 要素そのものを作業用の配列との間でビットごとコピーして移す
+
+// 整数値はすべて k 未満
+// O(n + k) time
+pub fn counting_sort<T: IntValue>(a: &mut [T], k: usize) {
+    for x in a.iter() {
+        assert!(x.int_value() < k as u64, "int_value must be less than k");
+    }
+    let mut g = PingPong::new(a);
+    unsafe { g.counting_sort_by(k, |x| x.int_value() as usize) };
+}
+
+// 下の桁から d ビットずつ counting sort する
+// 長さ 2^d の配列を使うので d は 16 以下にする
+// 最大の整数値のビット数を b として O((n + 2^d) b / d) time
+pub fn radix_sort<T: IntValue>(a: &mut [T], d: u32) {
+    assert!((1..=16).contains(&d), "d must be in 1..=16");
+    let max = a.iter().map(|x| x.int_value()).max().unwrap_or(0);
+    let bits = u64::BITS - max.leading_zeros();
+    let mask = (1_u64 << d) - 1;
+    let mut g = PingPong::new(a);
+    let mut shift = 0;
+    while shift < bits {
+        unsafe { g.counting_sort_by(1 << d, |x| (x.int_value() >> shift & mask) as usize) };
+        shift += d;
+    }
+}
+
+// 要素を a と同じ長さの作業用の配列 b との間で交互に移しながら並べ替える
+// b の長さは 0 のままにして、b の drop で要素を解放しないようにする
+// drop のときに要素が b にあれば a に書き戻すので、途中で int_value が panic しても a の要素は失われない
+struct PingPong<'a, T> {
+    a: &'a mut [T],
+    b: Vec<T>,
+    in_b: bool,
+}
+
+impl<'a, T> PingPong<'a, T> {
+    fn new(a: &'a mut [T]) -> Self {
+        let b = Vec::with_capacity(a.len());
+        Self { a, b, in_b: false }
+    }
+
+    // 要素を key の昇順に安定に並べて反対側に移す
+    // key は k 未満
+    // O(n + k) time
+    unsafe fn counting_sort_by<F>(&mut self, k: usize, key: F)
+    where
+        F: Fn(&T) -> usize,
+    {
+        let n = self.a.len();
+        let (src, dst) = if self.in_b {
+            (self.b.as_ptr(), self.a.as_mut_ptr())
+        } else {
+            (self.a.as_ptr(), self.b.as_mut_ptr())
+        };
+        let mut c = vec![0; k];
+        for i in 0..n {
+            c[key(&*src.add(i))] += 1;
+        }
+        // c[j] を key が j 以下の要素の個数にする
+        for j in 1..k {
+            c[j] += c[j - 1];
+        }
+        for i in (0..n).rev() {
+            let j = key(&*src.add(i));
+            c[j] -= 1;
+            ptr::copy_nonoverlapping(src.add(i), dst.add(c[j]), 1);
+        }
+        self.in_b = !self.in_b;
+    }
+}
+
+impl<'a, T> Drop for PingPong<'a, T> {
+    fn drop(&mut self) {
+        if self.in_b {
+            unsafe { ptr::copy_nonoverlapping(self.b.as_ptr(), self.a.as_mut_ptr(), self.a.len()) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        counting_sort, heap_sort, heap_sort_list, merge_sort, merge_sort_list, quick_sort,
+        quick_sort_list, quick_sort_list_with_rng, quick_sort_with_rng, radix_sort,
+    };
+    use array_stack::ArrayStack;
+    use binary_trie::IntValue;
+    use dllist::DLList;
+    use interface::List;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+    use skiplist_list::SkipListList;
+    use std::cell::Cell;
+    use std::cmp::{Ordering, Reverse};
+    use std::panic::{self, AssertUnwindSafe};
+    use std::rc::Rc;
+
+    type Sort = fn(&mut [(u32, usize)], fn(&(u32, usize), &(u32, usize)) -> Ordering) -> usize;
+
+    fn sorts() -> Vec<(&'static str, Sort)> {
+        vec![
+            ("merge_sort", merge_sort),
+            ("quick_sort", quick_sort),
+            ("heap_sort", heap_sort),
+        ]
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for (name, sort) in sorts() {
+            for n in 0..100 {
+                // (値, 元の位置)
+                let mut a: Vec<(u32, usize)> = (0..n).map(|i| (rng.gen_range(0..10), i)).collect();
+                let mut expected = a.clone();
+                expected.sort_by_key(|&(x, _)| x);
+                sort(&mut a, |x, y| x.0.cmp(&y.0));
+                let keys: Vec<u32> = a.iter().map(|&(x, _)| x).collect();
+                let expected_keys: Vec<u32> = expected.iter().map(|&(x, _)| x).collect();
+                assert_eq!(keys, expected_keys, "{}", name);
+                if name == "merge_sort" {
+                    // 安定
+                    assert_eq!(a, expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_comparator() {
+        let mut a = vec![3, 1, 4, 1, 5, 9, 2, 6];
+        merge_sort(&mut a, |x, y| y.cmp(x));
+        assert_eq!(a, vec![9, 6, 5, 4, 3, 2, 1, 1]);
+        quick_sort(&mut a, |x, y| x.cmp(y));
+        assert_eq!(a, vec![1, 1, 2, 3, 4, 5, 6, 9]);
+        heap_sort(&mut a, |x: &i32, y: &i32| {
+            (x % 3).cmp(&(y % 3)).then(x.cmp(y))
+        });
+        assert_eq!(a, vec![3, 6, 9, 1, 1, 4, 2, 5]);
+    }
+
+    #[test]
+    fn test_count() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let n = 1 << 12;
+        let log_n = 12;
+        let a: Vec<u32> = (0..n).map(|_| rng.gen()).collect();
+        // n log(n) 回程度に収まる
+        assert!(merge_sort(&mut a.clone(), Ord::cmp) <= n * log_n);
+        assert!(heap_sort(&mut a.clone(), Ord::cmp) <= 2 * n * log_n);
+        let mut rng = SmallRng::seed_from_u64(0);
+        assert!(quick_sort_with_rng(&mut a.clone(), Ord::cmp, &mut rng) <= 2 * n * log_n);
+        // 要素がすべて等しいと quick_sort は 1 回の分割で終わる
+        assert_eq!(quick_sort(&mut vec![0; n], Ord::cmp), n - 1);
+        assert_eq!(merge_sort(&mut Vec::<u32>::new(), Ord::cmp), 0);
+    }
+
+    fn to_vec<L: List<u32>>(list: &L) -> Vec<u32> {
+        (0..list.size()).map(|i| *list.get(i).unwrap()).collect()
+    }
+
+    fn test_list<L: List<u32> + Default>() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for n in 0..100 {
+            let mut list = L::default();
+            // 値を (上位, 元の位置) にして merge_sort_list が安定かどうかも確かめる
+            let mut expected = Vec::new();
+            for i in 0..n {
+                let x = rng.gen_range(0..10) << 16 | i;
+                list.add(i as usize, x);
+                expected.push(x);
+            }
+            let key = |x: &u32| x >> 16;
+            expected.sort_by_key(key);
+
+            merge_sort_list(&mut list, |x, y| key(y).cmp(&key(x)));
+            let mut desc = expected.clone();
+            desc.sort_by_key(|x| Reverse(key(x)));
+            assert_eq!(to_vec(&list), desc);
+            merge_sort_list(&mut list, |x, y| key(x).cmp(&key(y)));
+            let mut asc = desc.clone();
+            asc.sort_by_key(key);
+            assert_eq!(to_vec(&list), asc);
+
+            quick_sort_list_with_rng(&mut list, |x, y| y.cmp(x), &mut rng);
+            assert!(to_vec(&list).iter().eq(expected.iter().rev()));
+            quick_sort_list(&mut list, Ord::cmp);
+            assert_eq!(to_vec(&list), expected);
+            heap_sort_list(&mut list, |x, y| y.cmp(x));
+            assert!(to_vec(&list).iter().eq(expected.iter().rev()));
+            heap_sort_list(&mut list, Ord::cmp);
+            assert_eq!(to_vec(&list), expected);
+        }
+    }
+
+    #[test]
+    fn test_sort_list() {
+        test_list::<ArrayStack<u32>>();
+        test_list::<DLList<u32>>();
+        test_list::<SkipListList<u32>>();
+    }
+
+    #[test]
+    fn test_sort_list_panic() {
+        // 比較が panic しても要素は失われない
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut list = DLList::new();
+        for i in 0..100 {
+            list.add(i, rng.gen_range(0..100));
+        }
+        let mut expected = to_vec(&list);
+        expected.sort_unstable();
+        // k 回目より後の比較で panic する
+        let failing = |k| {
+            let mut count = 0;
+            move |x: &u32, y: &u32| {
+                count += 1;
+                assert!(count <= k, "compare failed");
+                x.cmp(y)
+            }
+        };
+        for k in [0, 1, 10, 100, 300] {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                merge_sort_list(&mut list, failing(k));
+            }));
+            assert!(result.is_err());
+            let mut v = to_vec(&list);
+            v.sort_unstable();
+            assert_eq!(v, expected);
+
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                quick_sort_list(&mut list, failing(k));
+            }));
+            assert!(result.is_err());
+            let mut v = to_vec(&list);
+            v.sort_unstable();
+            assert_eq!(v, expected);
+        }
+    }
+
+    thread_local! {
+        static COST: Cell<usize> = const { Cell::new(0) };
+    }
+
+    // DLList と同じく i 番目の操作に min(i, n-i) + 1 かかるとして、その合計を COST に数える
+    #[derive(Default)]
+    struct CostList(DLList<u32>);
+
+    impl CostList {
+        fn cost(&self, i: usize) {
+            let n = self.0.size();
+            COST.with(|c| c.set(c.get() + i.min(n.saturating_sub(i)) + 1));
+        }
+    }
+
+    impl List<u32> for CostList {
+        fn size(&self) -> usize {
+            self.0.size()
+        }
+
+        fn get(&self, i: usize) -> Option<&u32> {
+            self.cost(i);
+            self.0.get(i)
+        }
+
+        fn set(&self, i: usize, x: u32) -> u32 {
+            self.cost(i);
+            self.0.set(i, x)
+        }
+
+        fn add(&mut self, i: usize, x: u32) {
+            self.cost(i);
+            self.0.add(i, x)
+        }
+
+        fn remove(&mut self, i: usize) -> u32 {
+            self.cost(i);
+            self.0.remove(i)
+        }
+    }
+
+    #[test]
+    fn test_sort_list_count() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let n = 1 << 12;
+        let log_n = 12;
+        let mut list = CostList::default();
+        for i in 0..n {
+            list.add(i, rng.gen());
+        }
+        // 比較の回数も DLList への操作の手間も n log(n) 程度に収まる
+        // get, set で並べ替えると n^2 程度かかる
+        COST.with(|c| c.set(0));
+        assert!(merge_sort_list(&mut list, |x, y| y.cmp(x)) <= n * log_n);
+        assert!(COST.with(|c| c.get()) <= 10 * n * log_n);
+        COST.with(|c| c.set(0));
+        assert!(quick_sort_list_with_rng(&mut list, Ord::cmp, &mut rng) <= 2 * n * log_n);
+        assert!(COST.with(|c| c.get()) <= 16 * n * log_n);
+        assert!(to_vec(&list.0).windows(2).all(|w| w[0] <= w[1]));
+
+        // heap_sort_list は get, set で並べ替えるので ArrayStack で数える
+        let mut list = ArrayStack::new();
+        for i in 0..n {
+            list.add(i, rng.gen::<u32>());
+        }
+        assert!(heap_sort_list(&mut list, Ord::cmp) <= 2 * n * log_n);
+    }
+
+    #[test]
+    fn test_counting_sort() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for n in 0..100 {
+            let mut a: Vec<u8> = (0..n).map(|_| rng.gen_range(0..20)).collect();
+            let mut expected = a.clone();
+            expected.sort_unstable();
+            counting_sort(&mut a, 20);
+            assert_eq!(a, expected);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_counting_sort_out_of_range() {
+        counting_sort(&mut [1_u32, 5, 2], 5);
+    }
+
+    // 生きている要素を Rc で数える
+    // int_value を呼んだ回数が limit を超えると panic する
+    struct Failing {
+        x: u32,
+        calls: Rc<Cell<usize>>,
+        limit: usize,
+    }
+
+    impl IntValue for Failing {
+        fn int_value(&self) -> u64 {
+            self.calls.set(self.calls.get() + 1);
+            assert!(self.calls.get() <= self.limit, "int_value failed");
+            u64::from(self.x)
+        }
+    }
+
+    #[test]
+    fn test_int_sort_panic() {
+        // 途中で int_value が panic しても要素は失われず、二重に drop もしない
+        let mut rng = SmallRng::seed_from_u64(0);
+        let calls = Rc::new(Cell::new(0));
+        for limit in [0, 10, 100, 250, 400, 1000] {
+            let mut a: Vec<Failing> = (0..100)
+                .map(|_| Failing {
+                    x: rng.gen_range(0..1 << 12),
+                    calls: Rc::clone(&calls),
+                    limit,
+                })
+                .collect();
+            let mut expected: Vec<u32> = a.iter().map(|y| y.x).collect();
+            expected.sort_unstable();
+            calls.set(0);
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| radix_sort(&mut a, 4)));
+            let mut v: Vec<u32> = a.iter().map(|y| y.x).collect();
+            v.sort_unstable();
+            assert_eq!(v, expected);
+            calls.set(0);
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| counting_sort(&mut a, 1 << 12)));
+            let mut v: Vec<u32> = a.iter().map(|y| y.x).collect();
+            v.sort_unstable();
+            assert_eq!(v, expected);
+            assert_eq!(Rc::strong_count(&calls), 101);
+        }
+        assert_eq!(Rc::strong_count(&calls), 1);
+    }
+
+    #[test]
+    fn test_radix_sort() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for &d in &[1, 3, 8, 11, 16] {
+            for n in 0..50 {
+                let mut a: Vec<u64> = (0..n).map(|_| rng.gen()).collect();
+                let mut expected = a.clone();
+                expected.sort_unstable();
+                radix_sort(&mut a, d);
+                assert_eq!(a, expected);
+            }
+        }
+        let mut a = vec![u64::MAX, 0, u64::MAX - 1, 1];
+        radix_sort(&mut a, 8);
+        assert_eq!(a, vec![0, 1, u64::MAX - 1, u64::MAX]);
+        let mut a = vec!['c', 'a', 'b'];
+        radix_sort(&mut a, 4);
+        assert_eq!(a, vec!['a', 'b', 'c']);
+    }
+}
@@ -0,0 +1,311 @@
+use std::{cmp::Ordering, mem, ptr};
+
+use rand::{rngs::SmallRng, RngCore, SeedableRng};
+
+use interface::List;
+
+// 要素の位置をキーにした Treap
+// キーは持たずに、部分木のサイズから i 番目の要素を探す
+struct Node<T> {
+    x: T,
+    priority: u64, // 小さいほうが根側に来るようにする
+    size: usize,   // このノードを根とする部分木のノード数
+    left: *mut Node<T>,
+    right: *mut Node<T>,
+}
+
+pub struct TreapList<T> {
+    root: *mut Node<T>,
+    rng: SmallRng,
+}
+
+impl<T> TreapList<T> {
+    pub fn new() -> Self {
+        Self {
+            root: ptr::null_mut(),
+            rng: SmallRng::from_entropy(),
+        }
+    }
+
+    // O(1) time
+    fn size_u(u: *mut Node<T>) -> usize {
+        if u.is_null() {
+            0
+        } else {
+            unsafe { &*u }.size
+        }
+    }
+
+    // O(1) time
+    fn update_size(u: *mut Node<T>) {
+        let left = unsafe { &*u }.left;
+        let right = unsafe { &*u }.right;
+        unsafe { (*u).size = 1 + Self::size_u(left) + Self::size_u(right) };
+    }
+
+    // i 番目の要素をもつノードを返す
+    // i >= n のときは NULL を返す
+    // expected O(log(n)) time
+    fn get_node(&self, i: usize) -> *mut Node<T> {
+        let mut w = self.root;
+        let mut i = i;
+        while !w.is_null() {
+            let l = Self::size_u(unsafe { &*w }.left);
+            match i.cmp(&l) {
+                Ordering::Less => {
+                    w = unsafe { &*w }.left;
+                }
+                Ordering::Equal => {
+                    break;
+                }
+                Ordering::Greater => {
+                    i -= l + 1;
+                    w = unsafe { &*w }.right;
+                }
+            }
+        }
+        w
+    }
+
+    // u を根とする部分木を先頭の i 個からなる部分木と残りの部分木に分ける
+    // expected O(log(n)) time
+    fn split_node(u: *mut Node<T>, i: usize) -> (*mut Node<T>, *mut Node<T>) {
+        if u.is_null() {
+            return (ptr::null_mut(), ptr::null_mut());
+        }
+        let l = Self::size_u(unsafe { &*u }.left);
+        if i <= l {
+            let (left, right) = Self::split_node(unsafe { &*u }.left, i);
+            unsafe { (*u).left = right };
+            Self::update_size(u);
+            (left, u)
+        } else {
+            let (left, right) = Self::split_node(unsafe { &*u }.right, i - l - 1);
+            unsafe { (*u).right = left };
+            Self::update_size(u);
+            (u, right)
+        }
+    }
+
+    // u を根とする部分木の後ろに v を根とする部分木をつなげる
+    // expected O(log(n)) time
+    fn merge_node(u: *mut Node<T>, v: *mut Node<T>) -> *mut Node<T> {
+        if u.is_null() {
+            return v;
+        }
+        if v.is_null() {
+            return u;
+        }
+        if unsafe { &*u }.priority < unsafe { &*v }.priority {
+            let w = Self::merge_node(unsafe { &*u }.right, v);
+            unsafe { (*u).right = w };
+            Self::update_size(u);
+            u
+        } else {
+            let w = Self::merge_node(u, unsafe { &*v }.left);
+            unsafe { (*v).left = w };
+            Self::update_size(v);
+            v
+        }
+    }
+
+    // i 番目以降の要素を取り除いて、それらからなる TreapList を返す
+    // expected O(log(n)) time
+    pub fn split_at(&mut self, i: usize) -> TreapList<T> {
+        assert!(i <= self.size());
+        let (left, right) = Self::split_node(self.root, i);
+        self.root = left;
+        TreapList {
+            root: right,
+            rng: SmallRng::seed_from_u64(self.rng.next_u64()),
+        }
+    }
+
+    // other の要素をすべて末尾に移す
+    // expected O(log(n)) time
+    pub fn append(&mut self, other: &mut TreapList<T>) {
+        self.root = Self::merge_node(self.root, other.root);
+        other.root = ptr::null_mut();
+    }
+}
+
+impl<T> List<T> for TreapList<T> {
+    // O(1) time
+    fn size(&self) -> usize {
+        Self::size_u(self.root)
+    }
+
+    // expected O(log(n)) time
+    fn get(&self, i: usize) -> Option<&T> {
+        let u = self.get_node(i);
+        if u.is_null() {
+            None
+        } else {
+            Some(&unsafe { &*u }.x)
+        }
+    }
+
+    // expected O(log(n)) time
+    fn set(&self, i: usize, x: T) -> T {
+        let u = self.get_node(i);
+        assert!(!u.is_null(), "expect `i` < TreapList::size()");
+        mem::replace(unsafe { &mut (*u).x }, x)
+    }
+
+    // expected O(log(n)) time
+    fn add(&mut self, i: usize, x: T) {
+        assert!(i <= self.size());
+        let u = Box::into_raw(Box::new(Node {
+            x,
+            priority: self.rng.next_u64(),
+            size: 1,
+            left: ptr::null_mut(),
+            right: ptr::null_mut(),
+        }));
+        let (left, right) = Self::split_node(self.root, i);
+        self.root = Self::merge_node(Self::merge_node(left, u), right);
+    }
+
+    // expected O(log(n)) time
+    fn remove(&mut self, i: usize) -> T {
+        assert!(i < self.size());
+        let (left, right) = Self::split_node(self.root, i);
+        let (u, right) = Self::split_node(right, 1);
+        self.root = Self::merge_node(left, right);
+        debug_assert_eq!(Self::size_u(u), 1);
+        let u = unsafe { Box::from_raw(u) };
+        u.x
+    }
+}
+
+impl<T> Default for TreapList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for TreapList<T> {
+    fn drop(&mut self) {
+        let mut stack = vec![self.root];
+        while let Some(u) = stack.pop() {
+            if u.is_null() {
+                continue;
+            }
+            let u = unsafe { Box::from_raw(u) };
+            stack.push(u.left);
+            stack.push(u.right);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TreapList;
+    use interface::List;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+    #[test]
+    fn test_get_none() {
+        let mut list = TreapList::new();
+
+        let nil = list.get(0);
+        assert_eq!(nil, None);
+
+        list.add(0, 'a');
+        assert_eq!(list.get(1), None);
+        assert_eq!(list.get(2), None);
+    }
+
+    #[test]
+    fn test_set_get() {
+        let mut list = TreapList::new();
+        list.add(0, 'a');
+        list.add(1, 'b');
+        list.add(2, 'c');
+
+        // a b c
+        let old = list.set(0, 'x');
+        assert_eq!(old, 'a');
+        assert_eq!(list.get(0), Some(&'x'));
+
+        // x b c
+        let old = list.set(2, 'z');
+        assert_eq!(old, 'c');
+        assert_eq!(list.get(2), Some(&'z'));
+    }
+
+    #[test]
+    fn test_add_remove() {
+        let mut list = TreapList::new();
+        list.add(0, 'a');
+        list.add(1, 'b');
+        list.add(2, 'c');
+
+        // a b c
+        let b = list.remove(1);
+        assert_eq!(b, 'b');
+
+        // a c
+        let c = list.remove(1);
+        assert_eq!(c, 'c');
+
+        // a
+        let a = list.remove(0);
+        assert_eq!(a, 'a');
+
+        assert_eq!(list.size(), 0);
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut v = Vec::new();
+        let mut list = TreapList::new();
+        for i in 0..100 {
+            let i = rng.gen_range(0..=i);
+            v.insert(i, i);
+            list.add(i, i);
+        }
+        for i in 0..100 {
+            assert_eq!(list.get(i), v.get(i));
+        }
+        for i in (0..100).rev() {
+            let i = rng.gen_range(0..=i);
+            let x = v.remove(i);
+            let y = list.remove(i);
+            assert_eq!(x, y);
+        }
+    }
+
+    #[test]
+    fn test_split_at_append() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for _ in 0..20 {
+            let n = rng.gen_range(0..50);
+            let mut v: Vec<usize> = (0..n).collect();
+            let mut list = TreapList::new();
+            for i in 0..n {
+                list.add(i, i);
+            }
+            let i = rng.gen_range(0..=n);
+            let mut right = list.split_at(i);
+            let v_right = v.split_off(i);
+            assert_eq!(list.size(), v.len());
+            assert_eq!(right.size(), v_right.len());
+            for j in 0..v.len() {
+                assert_eq!(list.get(j), v.get(j));
+            }
+            for j in 0..v_right.len() {
+                assert_eq!(right.get(j), v_right.get(j));
+            }
+
+            list.append(&mut right);
+            v.extend(v_right);
+            assert_eq!(right.size(), 0);
+            assert_eq!(list.size(), v.len());
+            for j in 0..v.len() {
+                assert_eq!(list.get(j), v.get(j));
+            }
+        }
+    }
+}
@@ -4,18 +4,28 @@ use interface::SSet;
 use treap::Treap;
 
 fn main() {
-    let mut rng = SmallRng::seed_from_u64(122333); // Treap 内部で指定した seed と同じ値
+    let seed = 122333;
+    let mut rng = SmallRng::seed_from_u64(seed); // Treap 内部の rng と同じ seed
     let n = 10_000;
     let mut a = Vec::new();
     for _ in 0..n {
         a.push(rng.next_u64());
     }
 
+    // seed が分かっていると優先度と同じ順に要素を追加できてしまう
+    let mut treap = Treap::with_seed(seed);
+    for &a in &a {
+        treap.add(a);
+    }
+    println!("height = {}, size = {}", treap.height(), treap.size());
+
+    // Treap::new() はインスタンスごとに seed を決めるので偏らない
     let mut treap = Treap::new();
-    for a in a {
+    for &a in &a {
         treap.add(a);
     }
     println!("height = {}, size = {}", treap.height(), treap.size());
 
     // height = 9999, size = 10000
+    // height = 29, size = 10000 (実行ごとに変わる)
 }
@@ -1,228 +1,310 @@
 use rand::{rngs::SmallRng, RngCore, SeedableRng};
 use std::{
-    alloc,
-    cmp::{self, Ordering},
+    cmp,
     fmt::{self, Formatter},
+    marker::PhantomData,
+    mem,
+    ops::{Bound, RangeBounds},
     ptr,
+    vec,
 };
 
-use interface::SSet;
+use binary_search_tree as bst;
+use interface::{NavigableSSet, Pair, PairMap, PairSet, RangeSSet, SSet};
 
-struct Node<T> {
-    x: T,
-    priority: u64, // 小さいほうが根側に来るようにする
-    parent: *mut Node<T>,
-    left: *mut Node<T>,
-    right: *mut Node<T>,
-}
+// data は優先度で、小さいほうが根側に来るようにする
+type Node<T> = bst::Node<T, u64>;
 
-pub struct Treap<T> {
+pub struct Treap<T, R = SmallRng> {
     n: usize,
     root: *mut Node<T>,
-    rng: SmallRng,
+    rng: R,
 }
 
 impl<T> Treap<T> {
+    // seed はインスタンスごとに OS の乱数から決める
     pub fn new() -> Self {
+        Self::with_rng(SmallRng::from_entropy())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(SmallRng::seed_from_u64(seed))
+    }
+}
+
+impl<T> Default for Treap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, R> Treap<T, R>
+where
+    R: RngCore,
+{
+    pub fn with_rng(rng: R) -> Self {
         Self {
             n: 0,
             root: ptr::null_mut(),
-            rng: SmallRng::seed_from_u64(122333),
+            rng,
         }
     }
 
     fn gen_priority(&mut self) -> u64 {
         self.rng.next_u64()
     }
+}
 
-    fn rotate_right(&mut self, u: *mut Node<T>) {
-        //         u                      w
-        //         |                      |
-        //     +---+---+              +---+---+
-        //     |       |              |       |
-        //     w       c      ->      a       u
-        //     |                              |
-        // +---+---+                      +---+---+
-        // |       |                      |       |
-        // a       b                      b       c
-        let w = unsafe { &*u }.left;
-        debug_assert_ne!(w, ptr::null_mut());
-        let p = unsafe { (*u).parent };
-        if p == ptr::null_mut() {
-            debug_assert_eq!(self.root, u);
-            self.root = w;
-            unsafe { (*w).parent = ptr::null_mut() };
-        } else {
-            unsafe { (*w).parent = p };
-            if unsafe { (*p).left } == u {
-                unsafe { (*p).left = w };
-            } else {
-                debug_assert_eq!(unsafe { (*p).right }, u);
-                unsafe { (*p).right = w };
-            }
-        }
-        unsafe { (*u).parent = w };
-        let b = unsafe { (*w).right };
-        if b == ptr::null_mut() {
-            unsafe { (*u).left = ptr::null_mut() };
-        } else {
-            unsafe { (*b).parent = u };
-            unsafe { (*u).left = b };
-        }
-        unsafe { (*w).right = u };
-    }
-
-    fn rotate_left(&mut self, u: *mut Node<T>) {
-        //      u                         w
-        //      |                         |
-        //  +---+---+                 +---+---+
-        //  |       |                 |       |
-        //  a       w        ->       u       c
-        //          |                 |
-        //      +---+---+         +---+---+
-        //      |       |         |       |
-        //      b       c         a       b
-        let w = unsafe { &*u }.right;
-        debug_assert_ne!(w, ptr::null_mut());
-        let p = unsafe { (*u).parent };
-        if p == ptr::null_mut() {
-            debug_assert_eq!(self.root, u);
-            self.root = w;
-            unsafe { (*w).parent = ptr::null_mut() };
-        } else {
-            unsafe { (*w).parent = p };
-            if unsafe { (*p).left } == u {
-                unsafe { (*p).left = w };
-            } else {
-                debug_assert_eq!(unsafe { (*p).right }, u);
-                unsafe { (*p).right = w };
-            }
-        }
-        unsafe { (*u).parent = w };
-        let b = unsafe { (*w).left };
-        if b == ptr::null_mut() {
-            unsafe { (*u).right = ptr::null_mut() };
-        } else {
-            unsafe { (*b).parent = u };
-            unsafe { (*u).right = b };
-        }
-        unsafe { (*w).left = u };
+impl<T, R> Treap<T, R> {
+    // x より小さい要素の個数を返す
+    // expected O(log(n)) time
+    pub fn rank(&self, x: &T) -> usize
+    where
+        T: cmp::Ord,
+    {
+        unsafe { bst::rank(self.root, x) }
     }
-}
 
-impl<T> Treap<T>
-where
-    T: cmp::Ord,
-{
-    // - x に等しい要素を持つノードがあればそのノードを返す
-    // - そうでなければ、x を探索する経路で最後に通ったノードを返す
-    //   - ノードがもつ要素は x より小さいこともあれば大きいこともある
+    // 小さいほうから k 番目 (0-indexed) の要素を返す
     // expected O(log(n)) time
-    fn find_last(&self, x: &T) -> *mut Node<T> {
-        let mut w = self.root;
-        let mut prev = ptr::null_mut();
-        while w != ptr::null_mut() {
-            prev = w;
-            match x.cmp(&unsafe { &*w }.x) {
-                Ordering::Less => {
-                    w = unsafe { &*w }.left;
-                }
-                Ordering::Greater => {
-                    w = unsafe { &*w }.right;
-                }
-                Ordering::Equal => {
-                    return w;
-                }
-            }
-        }
-        prev
+    pub fn select(&self, k: usize) -> Option<&T> {
+        let u = unsafe { bst::select(self.root, k) };
+        unsafe { u.as_ref() }.map(|u| &u.x)
     }
 
-    // x を要素に持つノードを p の子として追加する
-    // O(1) time
-    fn add_child(&mut self, p: *mut Node<T>, x: T) -> *mut Node<T> {
-        let u = if p == ptr::null_mut() {
-            debug_assert_eq!(self.root, ptr::null_mut());
-            self.root = Box::into_raw(Box::new(Node {
-                x,
-                priority: self.gen_priority(),
-                parent: ptr::null_mut(),
-                left: ptr::null_mut(),
-                right: ptr::null_mut(),
-            }));
-            self.root
-        } else {
-            let y = &unsafe { &*p }.x;
-            let ord = x.cmp(y);
-
-            let u = Box::into_raw(Box::new(Node {
-                x,
-                priority: self.gen_priority(),
-                parent: p,
-                left: ptr::null_mut(),
-                right: ptr::null_mut(),
-            }));
-
-            match ord {
-                Ordering::Less => {
-                    debug_assert_eq!(unsafe { &*p }.left, ptr::null_mut());
-                    unsafe { (*p).left = u };
-                    u
-                }
-                Ordering::Greater => {
-                    debug_assert_eq!(unsafe { &*p }.right, ptr::null_mut());
-                    unsafe { (*p).right = u };
-                    u
-                }
-                Ordering::Equal => {
-                    unreachable!();
-                }
+    // 要素が最小のノードを返す
+    // expected O(log(n)) time
+    fn first_node(&self) -> *mut Node<T> {
+        unsafe { bst::first_node(self.root) }
+    }
+
+    // expected O(log(n)) time
+    fn partition_point_node<F>(&self, pred: F) -> *mut Node<T>
+    where
+        F: Fn(&T) -> bool,
+    {
+        unsafe { bst::partition_point_node(self.root, pred) }
+    }
+
+    // expected O(log(n)) time
+    fn find_pred_node_by<F>(&self, pred: F) -> *mut Node<T>
+    where
+        F: Fn(&T) -> bool,
+    {
+        unsafe { bst::find_pred_node_by(self.root, pred) }
+    }
+
+    // u を取り除いて、u が持っていた要素を返す
+    // expected O(log(n)) time
+    fn remove_node(&mut self, u: *mut Node<T>) -> T {
+        // trickle down
+        loop {
+            let left = unsafe { &*u }.left;
+            let right = unsafe { &*u }.right;
+            if left.is_null() && right.is_null() {
+                break;
             }
-        };
+            // 優先度の小さいほうの子を u の位置に上げる
+            if right.is_null()
+                || (!left.is_null() && unsafe { &*left }.data < unsafe { &*right }.data)
+            {
+                unsafe { bst::rotate_right(&mut self.root, u) };
+            } else {
+                unsafe { bst::rotate_left(&mut self.root, u) };
+            }
+        }
+        // 葉になったので外す
+        unsafe { bst::remove_node(&mut self.root, u) };
+        self.n -= 1;
+        let u = unsafe { Box::from_raw(u) };
+        u.x
+    }
 
-        self.n += 1;
-        u
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            u: self.first_node(),
+            _marker: PhantomData,
+        }
     }
 }
 
-impl<T> SSet<T> for Treap<T>
+impl<T, R> Treap<T, R>
 where
     T: cmp::Ord,
+    R: RngCore,
 {
-    // O(1) time
-    fn size(&self) -> usize {
-        self.n
+    // expected O(log(n)) time
+    fn find_last(&self, x: &T) -> *mut Node<T> {
+        unsafe { bst::find_last(self.root, x) }
     }
 
+    // x を追加して、x を持つノードを返す
+    // x に等しい要素がすでにあるときは何もせずに NULL を返す
     // expected O(log(n)) time
-    fn add(&mut self, x: T) -> bool {
+    fn add_node(&mut self, x: T) -> *mut Node<T> {
         let p = self.find_last(&x);
-        if p != ptr::null_mut() && unsafe { &*p }.x.eq(&x) {
-            return false;
+        if !p.is_null() && unsafe { &*p }.x.eq(&x) {
+            return ptr::null_mut();
         }
 
-        let u = self.add_child(p, x);
+        let u = Box::into_raw(Box::new(Node::new(x, self.gen_priority())));
+        unsafe { bst::add_child(&mut self.root, p, u) };
+        self.n += 1;
         // bubble up
         loop {
             let p = unsafe { &*u }.parent;
-            if p == ptr::null_mut() {
+            if p.is_null() {
                 break;
             }
-            if unsafe { &*p }.priority < unsafe { &*u }.priority {
+            if unsafe { &*p }.data < unsafe { &*u }.data {
                 break;
             }
             if unsafe { &*p }.right == u {
-                self.rotate_left(p);
+                unsafe { bst::rotate_left(&mut self.root, p) };
             } else if unsafe { &*p }.left == u {
-                self.rotate_right(p);
+                unsafe { bst::rotate_right(&mut self.root, p) };
             } else {
                 unreachable!();
             }
         }
-        if unsafe { &*u }.parent == ptr::null_mut() {
-            self.root = u;
+        u
+    }
+
+    // x 以上の要素をすべて取り除いて、それらからなる Treap を返す
+    // 返す Treap の rng は self.rng から作った seed で初期化する
+    // R: SeedableRng でないときは split_with_rng を使う
+    // expected O(log(n)) time
+    pub fn split(&mut self, x: &T) -> Treap<T, R>
+    where
+        R: SeedableRng,
+    {
+        let rng = R::seed_from_u64(self.rng.next_u64());
+        self.split_with_rng(x, rng)
+    }
+
+    // split と同じで、返す Treap は rng を使う
+    // expected O(log(n)) time
+    pub fn split_with_rng(&mut self, x: &T, rng: R) -> Treap<T, R> {
+        let (left, right) = unsafe { Self::split_node(self.root, |y| y < x) };
+        self.root = left;
+        self.n = unsafe { bst::size_u(left) };
+        Treap {
+            n: unsafe { bst::size_u(right) },
+            root: right,
+            rng,
         }
-        true
+    }
+
+    // self のすべての要素が other のすべての要素より小さいときに、2 つの Treap をひとつにする
+    // 返す Treap は self の rng を引き継ぎ、other の rng は捨てる
+    // expected O(log(n)) time
+    pub fn merge(mut self, mut other: Treap<T, R>) -> Treap<T, R> {
+        if let (Some(x), Some(y)) = (self.last(), other.first()) {
+            assert!(x < y, "expect self.last() < other.first()");
+        }
+        let root = mem::replace(&mut other.root, ptr::null_mut());
+        self.root = unsafe { Self::merge_node(self.root, root) };
+        self.n = unsafe { bst::size_u(self.root) };
+        self
+    }
+
+    // u を根とする部分木を pred(y) = true となる要素からなる部分木と、それ以外の要素からなる部分木に分ける
+    // 本では優先度が最小のダミーノードを根まで回転させて分けるが、ダミーに入れる T の値を用意できないので
+    // 根から辿りながらポインタを張り替える
+    // 木の高さが大きくなることもあるので再帰を使わない
+    // expected O(log(n)) time
+    unsafe fn split_node<F>(mut u: *mut Node<T>, pred: F) -> (*mut Node<T>, *mut Node<T>)
+    where
+        F: Fn(&T) -> bool,
+    {
+        let mut left = ptr::null_mut();
+        let mut right = ptr::null_mut();
+        // 次のノードをつなぐ場所と、その親
+        let mut left_slot: *mut *mut Node<T> = &mut left;
+        let mut left_parent = ptr::null_mut();
+        let mut right_slot: *mut *mut Node<T> = &mut right;
+        let mut right_parent = ptr::null_mut();
+        let mut path = Vec::new();
+        while !u.is_null() {
+            path.push(u);
+            if pred(&(*u).x) {
+                // u と u の左の子孫は左側に入る
+                *left_slot = u;
+                (*u).parent = left_parent;
+                left_parent = u;
+                left_slot = &mut (*u).right;
+                u = (*u).right;
+            } else {
+                *right_slot = u;
+                (*u).parent = right_parent;
+                right_parent = u;
+                right_slot = &mut (*u).left;
+                u = (*u).left;
+            }
+        }
+        *left_slot = ptr::null_mut();
+        *right_slot = ptr::null_mut();
+        // 子が変わりうるのは辿ったノードだけで、深いほうから直す
+        for &u in path.iter().rev() {
+            bst::update_size(u);
+        }
+        (left, right)
+    }
+
+    // u を根とする部分木と v を根とする部分木をつなげる
+    // u の要素はすべて v の要素より小さい
+    // u の右端と v の左端を優先度の順に並べてつなぎ直す
+    // expected O(log(n)) time
+    unsafe fn merge_node(mut u: *mut Node<T>, mut v: *mut Node<T>) -> *mut Node<T> {
+        let mut root = ptr::null_mut();
+        let mut slot: *mut *mut Node<T> = &mut root;
+        let mut parent = ptr::null_mut();
+        let mut path = Vec::new();
+        while !u.is_null() && !v.is_null() {
+            let w = if (*u).data < (*v).data {
+                // u が根で、u の右の子に残りをつなぐ
+                let w = u;
+                u = (*w).right;
+                *slot = w;
+                slot = &mut (*w).right;
+                w
+            } else {
+                // v が根で、v の左の子に残りをつなぐ
+                let w = v;
+                v = (*w).left;
+                *slot = w;
+                slot = &mut (*w).left;
+                w
+            };
+            (*w).parent = parent;
+            parent = w;
+            path.push(w);
+        }
+        let w = if u.is_null() { v } else { u };
+        *slot = w;
+        if !w.is_null() {
+            (*w).parent = parent;
+        }
+        for &u in path.iter().rev() {
+            bst::update_size(u);
+        }
+        root
+    }
+}
+
+impl<T, R> SSet<T> for Treap<T, R>
+where
+    T: cmp::Ord,
+    R: RngCore,
+{
+    // O(1) time
+    fn size(&self) -> usize {
+        self.n
+    }
+
+    // expected O(log(n)) time
+    fn add(&mut self, x: T) -> bool {
+        !self.add_node(x).is_null()
     }
 
     // expected O(log(n)) time
@@ -236,76 +318,179 @@ where
             return false;
         }
 
-        // trickle down
-        loop {
-            let left = unsafe { &*u }.left;
-            let right = unsafe { &*u }.right;
-            if left == ptr::null_mut() && right == ptr::null_mut() {
-                if self.root == u {
-                    self.root = ptr::null_mut();
-                } else {
-                    let p = unsafe { &*u }.parent;
-                    debug_assert_ne!(p, ptr::null_mut());
-                    if unsafe { &*p }.left == u {
-                        unsafe { (*p).left = ptr::null_mut() };
-                    } else if unsafe { &*p }.right == u {
-                        unsafe { (*p).right = ptr::null_mut() };
-                    } else {
-                        unreachable!();
-                    }
-                }
-                unsafe { ptr::drop_in_place(u) };
-                unsafe { alloc::dealloc(u as *mut u8, alloc::Layout::new::<Node<T>>()) };
-                break;
-            }
-            if left == ptr::null_mut() {
-                self.rotate_left(u);
-            } else if right == ptr::null_mut() {
-                self.rotate_right(u);
-            } else if unsafe { &*left }.priority < unsafe { &*right }.priority {
-                self.rotate_right(u);
-            } else {
-                self.rotate_left(u);
-            }
-        }
-        self.n -= 1;
+        self.remove_node(u);
         true
     }
 
-    // ほとんど find_last と同じ
-    // 最後に左に降りたときのノードを覚えておく
     // expected O(log(n))
     fn find(&self, x: &T) -> Option<&T> {
-        let mut w = self.root;
-        let mut z = ptr::null_mut();
-        loop {
-            if w == ptr::null_mut() {
-                break;
-            }
-            let y = &unsafe { &*w }.x;
-            match x.cmp(y) {
-                Ordering::Less => {
-                    z = w;
-                    w = unsafe { &*w }.left;
-                }
-                Ordering::Greater => {
-                    w = unsafe { &*w }.right;
-                }
-                Ordering::Equal => {
-                    return Some(y);
-                }
-            }
+        let u = self.partition_point_node(|y| y < x);
+        unsafe { u.as_ref() }.map(|u| &u.x)
+    }
+}
+
+pub struct Iter<'a, T> {
+    u: *mut Node<T>,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.u.is_null() {
+            return None;
         }
+        let x = &unsafe { &*self.u }.x;
+        self.u = unsafe { bst::next_node(self.u) };
+        Some(x)
+    }
+}
 
-        if z == ptr::null_mut() {
-            None
-        } else {
-            Some(&unsafe { &*z }.x)
+impl<'a, T, R> IntoIterator for &'a Treap<T, R> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct IntoIter<T> {
+    nodes: vec::IntoIter<*mut Node<T>>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let u = self.nodes.next()?;
+        let u = unsafe { Box::from_raw(u) };
+        Some(u.x)
+    }
+}
+
+impl<T> Drop for IntoIter<T> {
+    fn drop(&mut self) {
+        for _ in self {}
+    }
+}
+
+impl<T, R> IntoIterator for Treap<T, R> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        // 辿っている途中でノードを解放すると親に戻れなくなるので先に並べておく
+        let mut nodes = Vec::with_capacity(self.n);
+        let mut u = self.first_node();
+        while !u.is_null() {
+            nodes.push(u);
+            u = unsafe { bst::next_node(u) };
+        }
+        // ノードは IntoIter が解放する
+        self.root = ptr::null_mut();
+        IntoIter {
+            nodes: nodes.into_iter(),
+        }
+    }
+}
+
+impl<'a, T, R> RangeSSet<'a, T> for Treap<T, R>
+where
+    T: cmp::Ord + 'a,
+    R: RngCore,
+{
+    type Range = Range<'a, T>;
+
+    // expected O(log(n)) time
+    fn range<B>(&'a self, range: B) -> Self::Range
+    where
+        B: RangeBounds<T>,
+    {
+        // [u, end) を返す
+        let u = match range.start_bound() {
+            Bound::Included(x) => self.partition_point_node(|y| y < x),
+            Bound::Excluded(x) => self.partition_point_node(|y| y <= x),
+            Bound::Unbounded => self.first_node(),
+        };
+        let end = match range.end_bound() {
+            Bound::Included(x) => self.partition_point_node(|y| y <= x),
+            Bound::Excluded(x) => self.partition_point_node(|y| y < x),
+            Bound::Unbounded => ptr::null_mut(),
+        };
+        if !u.is_null() && !end.is_null() && unsafe { &*u }.x >= unsafe { &*end }.x {
+            // 始点が終点より後ろにあるので空
+            return Range {
+                u: end,
+                end,
+                _marker: PhantomData,
+            };
+        }
+        Range {
+            u,
+            end,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, R> NavigableSSet<T> for Treap<T, R>
+where
+    T: cmp::Ord,
+    R: RngCore,
+{
+    // expected O(log(n)) time
+    fn find_gt(&self, x: &T) -> Option<&T> {
+        let u = self.partition_point_node(|y| y <= x);
+        unsafe { u.as_ref() }.map(|u| &u.x)
+    }
+
+    // expected O(log(n)) time
+    fn find_le(&self, x: &T) -> Option<&T> {
+        let u = self.find_pred_node_by(|y| y <= x);
+        unsafe { u.as_ref() }.map(|u| &u.x)
+    }
+
+    // expected O(log(n)) time
+    fn find_lt(&self, x: &T) -> Option<&T> {
+        let u = self.find_pred_node_by(|y| y < x);
+        unsafe { u.as_ref() }.map(|u| &u.x)
+    }
+
+    // expected O(log(n)) time
+    fn first(&self) -> Option<&T> {
+        let u = self.first_node();
+        unsafe { u.as_ref() }.map(|u| &u.x)
+    }
+
+    // expected O(log(n)) time
+    fn last(&self) -> Option<&T> {
+        let u = self.find_pred_node_by(|_| true);
+        unsafe { u.as_ref() }.map(|u| &u.x)
+    }
+}
+
+pub struct Range<'a, T> {
+    u: *mut Node<T>,
+    end: *mut Node<T>,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for Range<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.u == self.end {
+            return None;
         }
+        let x = &unsafe { &*self.u }.x;
+        self.u = unsafe { bst::next_node(self.u) };
+        Some(x)
     }
 }
 
-impl<T> fmt::Debug for Treap<T>
+impl<T, R> fmt::Debug for Treap<T, R>
 where
     T: fmt::Debug,
 {
@@ -331,13 +516,14 @@ where
             } else {
                 writeln!(
                     f,
-                    "[{:p}] parent = {:p}, left = {:p}, right = {:p}, x = {:?}, priority = {}",
+                    "[{:p}] parent = {:p}, left = {:p}, right = {:p}, x = {:?}, priority = {}, size = {}",
                     u,
                     unsafe { &*u }.parent,
                     unsafe { &*u }.left,
                     unsafe { &*u }.right,
                     unsafe { &*u }.x,
-                    unsafe { &*u }.priority
+                    unsafe { &*u }.data,
+                    unsafe { &*u }.size
                 )?;
             }
         }
@@ -345,85 +531,127 @@ where
     }
 }
 
-impl<T> Treap<T> {
+impl<T, R> Drop for Treap<T, R> {
+    fn drop(&mut self) {
+        unsafe { bst::drop_tree(self.root) };
+    }
+}
+
+impl<T, R> Treap<T, R> {
     pub fn height(&self) -> usize {
-        use std::collections;
-        let mut max_depth = 0;
-        let mut que = collections::VecDeque::new();
-        que.push_back((self.root, 0));
-        while let Some((u, d)) = que.pop_front() {
-            if u == ptr::null_mut() {
-                continue;
-            }
-            max_depth = max_depth.max(d);
-            que.push_back((unsafe { &*u }.left, d + 1));
-            que.push_back((unsafe { &*u }.right, d + 1));
+        unsafe { bst::height(self.root) }
+    }
+}
+
+impl<K, V, R> Treap<Pair<K, V>, R>
+where
+    K: cmp::Ord,
+{
+    // key をキーに持つノードを返す
+    // なければ NULL を返す
+    // expected O(log(n)) time
+    fn find_pair_node(&self, key: &K) -> *mut Node<Pair<K, V>> {
+        unsafe { bst::find_node_by(self.root, |p| p.key < *key, |p| p.key == *key) }
+    }
+}
+
+// Treap の要素を (キー, 値) にしたもの
+pub type TreapMap<K, V, R = SmallRng> = PairMap<K, V, Treap<Pair<K, V>, R>>;
+
+impl<K, V, R> PairSet<K, V> for Treap<Pair<K, V>, R>
+where
+    K: cmp::Ord,
+    R: RngCore,
+{
+    // expected O(log(n)) time
+    fn get_pair(&self, key: &K) -> Option<&Pair<K, V>> {
+        unsafe { self.find_pair_node(key).as_ref() }.map(|u| &u.x)
+    }
+
+    // expected O(log(n)) time
+    fn get_pair_mut(&mut self, key: &K) -> Option<&mut Pair<K, V>> {
+        unsafe { self.find_pair_node(key).as_mut() }.map(|u| &mut u.x)
+    }
+
+    // expected O(log(n)) time
+    fn find_pair(&self, key: &K) -> Option<&Pair<K, V>> {
+        let u = self.partition_point_node(|p| p.key < *key);
+        unsafe { u.as_ref() }.map(|u| &u.x)
+    }
+
+    // expected O(log(n)) time
+    fn add_pair(&mut self, p: Pair<K, V>) -> &mut Pair<K, V> {
+        let u = self.add_node(p);
+        unsafe { &mut (*u).x }
+    }
+
+    // expected O(log(n)) time
+    fn remove_pair(&mut self, key: &K) -> Option<Pair<K, V>> {
+        let u = self.find_pair_node(key);
+        if u.is_null() {
+            None
+        } else {
+            Some(self.remove_node(u))
         }
-        max_depth
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Node, Treap};
-    use interface::SSet;
-    use rand::{rngs::SmallRng, Rng, SeedableRng};
+    use super::{bst, Node, Treap, TreapMap};
+    use interface::{testing, SSet};
+    use rand::rngs::{mock::StepRng, SmallRng};
+    use rand::{Rng, SeedableRng};
     use std::collections::BTreeSet;
-    use std::ptr;
+    use std::rc::Rc;
 
     #[test]
-    fn test_rotate() {
-        macro_rules! node {
-            ($x: expr, $left: expr, $right: expr) => {
-                Box::into_raw(Box::new(Node {
-                    x: $x,
-                    priority: 0,
-                    parent: ptr::null_mut(),
-                    left: $left,
-                    right: $right,
-                }))
-            };
+    fn test_with_seed() {
+        let mut treap_1 = Treap::with_seed(42);
+        let mut treap_2 = Treap::with_seed(42);
+        for x in 0..100 {
+            treap_1.add(x);
+            treap_2.add(x);
         }
-        let mut treap = Treap::new();
-        let a = node!('a', ptr::null_mut(), ptr::null_mut());
-        let b = node!('b', ptr::null_mut(), ptr::null_mut());
-        let c = node!('c', ptr::null_mut(), ptr::null_mut());
-        let w = node!('w', a, b);
-        let u = node!('u', w, c);
-        let r = node!('r', u, ptr::null_mut());
-        unsafe {
-            (*a).parent = w;
-            (*b).parent = w;
-            (*c).parent = u;
-            (*w).parent = u;
-            (*u).parent = r;
-        };
-        treap.root = r;
+        assert_eq!(treap_1.height(), treap_2.height());
+        let priority_1 = unsafe { &*treap_1.root }.data;
+        let priority_2 = unsafe { &*treap_2.root }.data;
+        assert_eq!(priority_1, priority_2);
+    }
 
-        treap.rotate_right(u);
+    #[test]
+    fn test_with_rng() {
+        // 優先度が 0, 1, 2, ... の順に決まるので昇順に追加すると一直線になる
+        let mut treap = Treap::with_rng(StepRng::new(0, 1));
+        for x in 0..100 {
+            treap.add(x);
+        }
+        assert_eq!(treap.height(), 99);
+    }
 
-        assert_eq!(unsafe { (*a).parent }, w);
-        assert_eq!(unsafe { (*b).parent }, u);
-        assert_eq!(unsafe { (*c).parent }, u);
-        assert_eq!(unsafe { (*w).parent }, r);
-        assert_eq!(unsafe { (*w).left }, a);
-        assert_eq!(unsafe { (*w).right }, u);
-        assert_eq!(unsafe { (*u).parent }, w);
-        assert_eq!(unsafe { (*u).left }, b);
-        assert_eq!(unsafe { (*u).right }, c);
-        assert_eq!(unsafe { (*r).left }, w);
+    #[test]
+    fn test_new() {
+        // seed は OS の乱数から決めるので、結果が seed によらないことだけ確かめる
+        let mut treap = Treap::new();
+        let mut other = Treap::default();
+        for x in 0..100 {
+            treap.add(x);
+            other.add(x);
+        }
+        assert!(treap.iter().eq(other.iter()));
+        assert!(treap.iter().copied().eq(0..100));
     }
 
     #[test]
     fn remove_from_empty_set() {
-        let mut treap = Treap::new();
+        let mut treap = Treap::with_seed(0);
         let removed = treap.remove(&42);
         assert!(!removed);
     }
 
     #[test]
     fn add_same() {
-        let mut treap = Treap::new();
+        let mut treap = Treap::with_seed(0);
         let added = treap.add(42);
         assert!(added);
         let added = treap.add(42);
@@ -432,7 +660,7 @@ mod tests {
 
     #[test]
     fn add_remove() {
-        let mut treap = Treap::new();
+        let mut treap = Treap::with_seed(0);
         treap.add(42);
         assert_eq!(treap.size(), 1);
         let removed = treap.remove(&42);
@@ -442,7 +670,7 @@ mod tests {
 
     #[test]
     fn find_less_equal_greater() {
-        let mut treap = Treap::new();
+        let mut treap = Treap::with_seed(0);
         treap.add(42);
         assert_eq!(treap.find(&41), Some(&42));
         assert_eq!(treap.find(&42), Some(&42));
@@ -452,7 +680,7 @@ mod tests {
     #[test]
     fn test_random() {
         let mut rng = SmallRng::seed_from_u64(0);
-        let mut treap = Treap::new();
+        let mut treap = Treap::with_seed(0);
         let mut btree_set = BTreeSet::new();
 
         for _ in 0..100 {
@@ -476,4 +704,140 @@ mod tests {
             assert_eq!(removed_1, removed_2);
         }
     }
+
+    #[test]
+    fn test_iter() {
+        testing::test_iter(Treap::with_seed(0));
+    }
+
+    #[test]
+    fn test_range() {
+        testing::test_range(Treap::with_seed(0));
+    }
+
+    #[test]
+    fn test_rank_select() {
+        testing::test_rank_select(Treap::with_seed(0), Treap::rank, Treap::select);
+    }
+
+    // 二分探索木の性質, ヒープの性質, parent, size が正しいか調べる
+    fn validate<T: Ord>(treap: &Treap<T>) {
+        let check = |node: &Node<T>| {
+            for &c in &[node.left, node.right] {
+                if !c.is_null() {
+                    assert!(node.data <= unsafe { &*c }.data);
+                }
+            }
+        };
+        assert_eq!(
+            unsafe { bst::testing::validate(treap.root, check) },
+            treap.size()
+        );
+    }
+
+    #[test]
+    fn test_split_merge() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for _ in 0..20 {
+            let mut treap = Treap::with_seed(0);
+            let mut btree_set = BTreeSet::new();
+            for _ in 0..100 {
+                let x = rng.gen_range(0..100_u8);
+                treap.add(x);
+                btree_set.insert(x);
+            }
+            let x = rng.gen_range(0..110_u8);
+            let right = treap.split(&x);
+            let btree_right = btree_set.split_off(&x);
+            validate(&treap);
+            validate(&right);
+            assert!(treap.iter().eq(btree_set.iter()));
+            assert!(right.iter().eq(btree_right.iter()));
+
+            let treap = treap.merge(right);
+            btree_set.extend(btree_right);
+            validate(&treap);
+            assert!(treap.iter().eq(btree_set.iter()));
+        }
+    }
+
+    #[test]
+    fn test_split_merge_empty() {
+        let mut treap = Treap::with_seed(0);
+        let right = treap.split(&0);
+        assert_eq!(right.size(), 0);
+        let mut treap = treap.merge(right);
+        treap.add(1);
+        let mut right = treap.split(&0);
+        assert_eq!(treap.size(), 0);
+        assert_eq!(right.size(), 1);
+        right.add(2);
+        let treap = treap.merge(right);
+        assert!(treap.iter().eq([1, 2].iter()));
+    }
+
+    #[test]
+    fn test_split_merge_deep() {
+        // 優先度が 2^64 - 1, 2^64 - 2, ... の順に決まるので、昇順に追加すると毎回根まで回転して左に一直線になる
+        // 一直線の木でも split と merge はスタックを使い切らない
+        let n = 1_000_000;
+        let mut treap = Treap::with_rng(StepRng::new(u64::MAX, u64::MAX));
+        for x in 0..n {
+            treap.add(x);
+        }
+        assert_eq!(treap.height(), n - 1);
+        let right = treap.split_with_rng(&(n / 2), StepRng::new(0, 1));
+        assert_eq!(treap.size(), n / 2);
+        assert_eq!(right.size(), n - n / 2);
+        assert!(treap.iter().copied().eq(0..n / 2));
+        assert!(right.iter().copied().eq(n / 2..n));
+        let treap = treap.merge(right);
+        assert_eq!(treap.height(), n - 1);
+        assert!(treap.iter().copied().eq(0..n));
+    }
+
+    #[test]
+    fn test_drop() {
+        // split, merge, into_iter をしても要素は一度ずつ drop される
+        let x = Rc::new(());
+        let mut treap = Treap::with_seed(0);
+        for i in 0..100 {
+            treap.add((i, Rc::clone(&x)));
+        }
+        let right = treap.split(&(50, Rc::clone(&x)));
+        assert_eq!(Rc::strong_count(&x), 101);
+        drop(right);
+        assert_eq!(Rc::strong_count(&x), 51);
+        let mut right = Treap::with_seed(0);
+        for i in 50..100 {
+            right.add((i, Rc::clone(&x)));
+        }
+        let treap = treap.merge(right);
+        let mut iter = treap.into_iter();
+        iter.next();
+        assert_eq!(Rc::strong_count(&x), 100);
+        drop(iter);
+        assert_eq!(Rc::strong_count(&x), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_merge_overlap() {
+        let mut treap = Treap::with_seed(0);
+        treap.add(2);
+        let mut other = Treap::with_seed(0);
+        other.add(1);
+        treap.merge(other);
+    }
+
+    #[test]
+    fn test_navigate() {
+        testing::test_navigate(Treap::with_seed(0));
+    }
+
+    #[test]
+    fn test_map() {
+        testing::test_map_random(TreapMap::with_set(Treap::with_seed(0)));
+        testing::test_map_entry(TreapMap::with_set(Treap::with_seed(0)));
+    }
 }
@@ -0,0 +1,211 @@
+use std::{collections::HashMap, ops::RangeBounds};
+
+use binary_trie::{BinaryTrie, IntValue, Iter, Node, PrefixIndex, Range};
+use interface::{NavigableSSet, RangeSSet, SSet};
+
+// BinaryTrie の各深さのノードを、根からの経路 (ix の上位ビット) をキーとするハッシュテーブルにも入れておく
+// 経路が存在する深さを二分探索できる
+struct PrefixTable<T> {
+    // t[i] は深さ i のノードを持つ
+    // 根は BinaryTrie から渡されるので t[0] は使わない
+    t: Vec<HashMap<u64, *mut Node<T>>>,
+}
+
+impl<T> PrefixTable<T> {
+    fn new() -> Self {
+        let w = u64::BITS as usize;
+        Self {
+            t: vec![HashMap::new(); w + 1],
+        }
+    }
+}
+
+impl<T> PrefixIndex<T> for PrefixTable<T> {
+    // O(log(w)) time
+    unsafe fn find_prefix(&self, root: *mut Node<T>, ix: u64) -> *mut Node<T> {
+        let w = u64::BITS;
+        // 深さ l のノード u までは経路があり、深さ h のノードはない
+        let mut l = 0;
+        let mut h = w + 1;
+        let mut u = root;
+        while h - l > 1 {
+            let i = (l + h) / 2;
+            match self.t[i as usize].get(&(ix >> (w - i))) {
+                Some(&v) => {
+                    l = i;
+                    u = v;
+                }
+                None => {
+                    h = i;
+                }
+            }
+        }
+        u
+    }
+
+    fn insert(&mut self, i: u32, prefix: u64, u: *mut Node<T>) {
+        self.t[i as usize].insert(prefix, u);
+    }
+
+    fn remove(&mut self, i: u32, prefix: u64) {
+        self.t[i as usize].remove(&prefix);
+    }
+}
+
+// 探索は O(log(w)) time で、追加と削除は BinaryTrie と同じく O(w) time
+pub struct XFastTrie<T> {
+    trie: BinaryTrie<T, PrefixTable<T>>,
+}
+
+impl<T> XFastTrie<T> {
+    pub fn new() -> Self {
+        Self {
+            trie: BinaryTrie::with_index(PrefixTable::new()),
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.trie.iter()
+    }
+}
+
+impl<T> Default for XFastTrie<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SSet<T> for XFastTrie<T>
+where
+    T: IntValue,
+{
+    fn size(&self) -> usize {
+        self.trie.size()
+    }
+
+    // O(w) time
+    fn add(&mut self, x: T) -> bool {
+        self.trie.add(x)
+    }
+
+    // O(w) time
+    fn remove(&mut self, x: &T) -> bool {
+        self.trie.remove(x)
+    }
+
+    // O(log(w)) time
+    fn find(&self, x: &T) -> Option<&T> {
+        self.trie.find(x)
+    }
+}
+
+impl<'a, T> RangeSSet<'a, T> for XFastTrie<T>
+where
+    T: IntValue + 'a,
+{
+    type Range = Range<'a, T>;
+
+    // 始点と終点を探すのに O(log(w)) time
+    fn range<R>(&'a self, range: R) -> Self::Range
+    where
+        R: RangeBounds<T>,
+    {
+        self.trie.range(range)
+    }
+}
+
+impl<T> NavigableSSet<T> for XFastTrie<T>
+where
+    T: IntValue,
+{
+    // O(log(w)) time
+    fn find_gt(&self, x: &T) -> Option<&T> {
+        self.trie.find_gt(x)
+    }
+
+    // O(log(w)) time
+    fn find_le(&self, x: &T) -> Option<&T> {
+        self.trie.find_le(x)
+    }
+
+    // O(log(w)) time
+    fn find_lt(&self, x: &T) -> Option<&T> {
+        self.trie.find_lt(x)
+    }
+
+    // O(1) time
+    fn first(&self) -> Option<&T> {
+        self.trie.first()
+    }
+
+    // O(1) time
+    fn last(&self) -> Option<&T> {
+        self.trie.last()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a XFastTrie<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::XFastTrie;
+    use interface::{testing, SSet};
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_find() {
+        let mut trie = XFastTrie::<u8>::new();
+        assert_eq!(trie.find(&0), None);
+        trie.add(0);
+        trie.add(10);
+        trie.add(100);
+        assert_eq!(trie.find(&0), Some(&0));
+        assert_eq!(trie.find(&1), Some(&10));
+        assert_eq!(trie.find(&10), Some(&10));
+        assert_eq!(trie.find(&11), Some(&100));
+        assert_eq!(trie.find(&101), None);
+        assert!(trie.remove(&10));
+        assert!(!trie.remove(&10));
+        assert_eq!(trie.find(&1), Some(&100));
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut trie = XFastTrie::<u32>::new();
+        let mut btree_set = BTreeSet::new();
+        for _ in 0..3000 {
+            let x = rng.gen_range(0..300);
+            if rng.gen_bool(0.5) {
+                assert_eq!(trie.add(x), btree_set.insert(x));
+            } else {
+                assert_eq!(trie.remove(&x), btree_set.remove(&x));
+            }
+            assert_eq!(trie.size(), btree_set.len());
+            let x = rng.gen_range(0..310);
+            assert_eq!(trie.find(&x), btree_set.range(x..).next());
+            // 最下段のハッシュテーブルには葉がちょうど入っている
+            let t = &trie.trie.index().t;
+            assert_eq!(t[u64::BITS as usize].len(), btree_set.len());
+        }
+        assert!(trie.iter().eq(btree_set.iter()));
+    }
+
+    #[test]
+    fn test_range() {
+        testing::test_range(XFastTrie::new());
+    }
+
+    #[test]
+    fn test_navigate() {
+        testing::test_navigate(XFastTrie::new());
+    }
+}
@@ -0,0 +1,309 @@
+use rand::{rngs::SmallRng, Rng, RngCore, SeedableRng};
+use std::{cmp::Ordering, mem, ptr};
+
+use binary_trie::IntValue;
+use interface::SSet;
+use treap::Treap;
+use x_fast_trie::XFastTrie;
+
+// Treap に入れるために整数値で比べる
+#[repr(transparent)]
+struct Key<T>(T);
+
+impl<T> Key<T> {
+    // repr(transparent) なので &T を &Key<T> として読める
+    fn from_ref(x: &T) -> &Key<T> {
+        unsafe { &*(x as *const T as *const Key<T>) }
+    }
+}
+
+impl<T: IntValue> PartialEq for Key<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.int_value() == other.0.int_value()
+    }
+}
+
+impl<T: IntValue> Eq for Key<T> {}
+
+impl<T: IntValue> PartialOrd for Key<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: IntValue> Ord for Key<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.int_value().cmp(&other.0.int_value())
+    }
+}
+
+// 整数値が (ひとつ前の Bucket の ix, ix] の範囲にある要素を Treap に持つ
+// XFastTrie からは &Bucket しか得られないので Treap はポインタで持つ
+struct Bucket<T> {
+    ix: u64,
+    t: *mut Treap<Key<T>>,
+}
+
+impl<T> Bucket<T> {
+    // XFastTrie を引くためだけに使う
+    fn probe(ix: u64) -> Self {
+        Self {
+            ix,
+            t: ptr::null_mut(),
+        }
+    }
+}
+
+impl<T> IntValue for Bucket<T> {
+    fn int_value(&self) -> u64 {
+        self.ix
+    }
+}
+
+// 要素をおよそ w = T::BITS 個に 1 個の割合で選んで XFastTrie に入れ、残りを Treap に入れる
+// ix = 2^64 - 1 の Bucket は常にあり、選ばれた最大の要素より大きい要素を持つ
+pub struct YFastTrie<T, R = SmallRng> {
+    n: usize,
+    xft: XFastTrie<Bucket<T>>,
+    rng: R,
+}
+
+impl<T> YFastTrie<T> {
+    // seed はインスタンスごとに OS の乱数から決める
+    pub fn new() -> Self {
+        Self::with_rng(SmallRng::from_entropy())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(SmallRng::seed_from_u64(seed))
+    }
+}
+
+impl<T> Default for YFastTrie<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, R> YFastTrie<T, R>
+where
+    R: RngCore,
+{
+    pub fn with_rng(mut rng: R) -> Self {
+        let mut xft = XFastTrie::new();
+        xft.add(Bucket {
+            ix: u64::MAX,
+            t: Box::into_raw(Box::new(Treap::with_seed(rng.next_u64()))),
+        });
+        Self { n: 0, xft, rng }
+    }
+}
+
+impl<T, R> YFastTrie<T, R> {
+    // ix 以上で最小の ix を持つ Bucket の Treap を返す
+    // O(log(w)) time
+    fn find_bucket(&self, ix: u64) -> (u64, *mut Treap<Key<T>>) {
+        // ix = 2^64 - 1 の Bucket があるので必ず見つかる
+        let b = self.xft.find(&Bucket::probe(ix)).unwrap();
+        (b.ix, b.t)
+    }
+}
+
+impl<T, R> SSet<T> for YFastTrie<T, R>
+where
+    T: IntValue,
+    R: RngCore,
+{
+    // O(1) time
+    fn size(&self) -> usize {
+        self.n
+    }
+
+    // expected O(log(w)) time
+    fn add(&mut self, x: T) -> bool {
+        // 本の w は整数値のビット数なので、T の整数値が取りうる範囲から決める
+        let w = T::BITS;
+        let ix = x.int_value();
+        let (_, t) = self.find_bucket(ix);
+        let t = unsafe { &mut *t };
+        let x = Key(x);
+        if t.find(&x) == Some(&x) {
+            return false;
+        }
+        // ix = 2^64 - 1 は常に Bucket があるので選ばない
+        if ix != u64::MAX && self.rng.gen_ratio(1, w) {
+            // x 以上の要素は元の Bucket に残し、x 未満の要素と x で新しい Bucket を作る
+            let right = t.split(&x);
+            let mut left = mem::replace(t, right);
+            left.add(x);
+            self.xft.add(Bucket {
+                ix,
+                t: Box::into_raw(Box::new(left)),
+            });
+        } else {
+            t.add(x);
+        }
+        self.n += 1;
+        true
+    }
+
+    // expected O(log(w)) time
+    fn remove(&mut self, x: &T) -> bool {
+        let ix = x.int_value();
+        let (bucket_ix, t) = self.find_bucket(ix);
+        if !unsafe { &mut *t }.remove(Key::from_ref(x)) {
+            return false;
+        }
+        if bucket_ix == ix && ix != u64::MAX {
+            // x の Bucket をなくして、要素を次の Bucket に移す
+            let (_, next) = self.find_bucket(ix + 1);
+            self.xft.remove(&Bucket::probe(ix));
+            let left = unsafe { Box::from_raw(t) };
+            unsafe { ptr::write(next, left.merge(ptr::read(next))) };
+        }
+        self.n -= 1;
+        true
+    }
+
+    // expected O(log(w)) time
+    fn find(&self, x: &T) -> Option<&T> {
+        // ix = 2^64 - 1 以外の Bucket は自身の ix を持つ要素を含むので、答えは必ずこの Bucket にある
+        let (_, t) = self.find_bucket(x.int_value());
+        unsafe { &*t }.find(Key::from_ref(x)).map(|y| &y.0)
+    }
+}
+
+impl<T, R> Drop for YFastTrie<T, R> {
+    fn drop(&mut self) {
+        for b in self.xft.iter() {
+            unsafe { drop(Box::from_raw(b.t)) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::YFastTrie;
+    use binary_trie::IntValue;
+    use interface::SSet;
+    use rand::rngs::{mock::StepRng, SmallRng};
+    use rand::{Rng, SeedableRng};
+    use std::collections::BTreeSet;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_find() {
+        let mut trie = YFastTrie::<u64>::new();
+        assert_eq!(trie.find(&0), None);
+        trie.add(0);
+        trie.add(10);
+        trie.add(100);
+        trie.add(u64::MAX);
+        assert!(!trie.add(10));
+        assert_eq!(trie.find(&0), Some(&0));
+        assert_eq!(trie.find(&1), Some(&10));
+        assert_eq!(trie.find(&10), Some(&10));
+        assert_eq!(trie.find(&11), Some(&100));
+        assert_eq!(trie.find(&101), Some(&u64::MAX));
+        assert!(trie.remove(&u64::MAX));
+        assert!(!trie.remove(&u64::MAX));
+        assert_eq!(trie.find(&101), None);
+        assert_eq!(trie.size(), 3);
+    }
+
+    fn test_random_with<R: rand::RngCore>(mut trie: YFastTrie<u32, R>) {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut btree_set = BTreeSet::new();
+        for _ in 0..3000 {
+            let x = rng.gen_range(0..300);
+            if rng.gen_bool(0.5) {
+                assert_eq!(trie.add(x), btree_set.insert(x));
+            } else {
+                assert_eq!(trie.remove(&x), btree_set.remove(&x));
+            }
+            assert_eq!(trie.size(), btree_set.len());
+            let x = rng.gen_range(0..310);
+            assert_eq!(trie.find(&x), btree_set.range(x..).next());
+        }
+    }
+
+    #[test]
+    fn test_random() {
+        test_random_with(YFastTrie::with_seed(0));
+    }
+
+    #[test]
+    fn test_with_rng() {
+        // すべての要素が XFastTrie に入る
+        let trie = YFastTrie::with_rng(StepRng::new(0, 0));
+        test_random_with(trie);
+        // どの要素も XFastTrie に入らない
+        let trie = YFastTrie::with_rng(StepRng::new(u64::MAX, 0));
+        test_random_with(trie);
+
+        let mut trie = YFastTrie::with_rng(StepRng::new(0, 0));
+        for x in 0..100_u32 {
+            trie.add(x);
+        }
+        assert_eq!(trie.xft.size(), 101);
+        let mut trie = YFastTrie::with_rng(StepRng::new(u64::MAX, 0));
+        for x in 0..100_u32 {
+            trie.add(x);
+        }
+        assert_eq!(trie.xft.size(), 1);
+    }
+
+    #[test]
+    fn test_sampling_rate() {
+        // u32 ならおよそ 32 個に 1 個を選ぶ
+        let mut trie = YFastTrie::<u32>::with_seed(0);
+        for x in 0..64000 {
+            trie.add(x);
+        }
+        assert!((1500..2500).contains(&trie.xft.size()));
+        let mut trie = YFastTrie::<u64>::with_seed(0);
+        for x in 0..64000 {
+            trie.add(x);
+        }
+        assert!((750..1250).contains(&trie.xft.size()));
+    }
+
+    // 生きている要素を Rc で数える
+    struct Counted {
+        x: u32,
+        _count: Rc<()>,
+    }
+
+    impl IntValue for Counted {
+        fn int_value(&self) -> u64 {
+            u64::from(self.x)
+        }
+    }
+
+    #[test]
+    fn test_drop() {
+        let count = Rc::new(());
+        let mut trie = YFastTrie::with_seed(0);
+        for i in 0..1000 {
+            trie.add(Counted {
+                x: i,
+                _count: Rc::clone(&count),
+            });
+        }
+        assert!(!trie.add(Counted {
+            x: 0,
+            _count: Rc::clone(&count),
+        }));
+        assert_eq!(Rc::strong_count(&count), 1001);
+        // Bucket をなくして Treap をつなぐ場合も含む
+        for i in (0..1000).step_by(2) {
+            assert!(trie.remove(&Counted {
+                x: i,
+                _count: Rc::clone(&count),
+            }));
+        }
+        assert_eq!(Rc::strong_count(&count), 501);
+        drop(trie);
+        assert_eq!(Rc::strong_count(&count), 1);
+    }
+}